@@ -0,0 +1,49 @@
+use std::io::{empty, sink};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use nyanfmt::{compile_code, Executor};
+
+fn executors(c: &mut Criterion) {
+	// the number of samples, lowered for the programs running for seconds
+	// without optimization
+	let programs = [
+		(
+			"hello",
+			include_str!("../samples/hello.nyan"),
+			100,
+		),
+		(
+			"sierpinski",
+			include_str!("../samples/sierpinski.nyan"),
+			100,
+		),
+		(
+			"mandelbrot",
+			include_str!("../samples/mandelbrot.nyan"),
+			10,
+		),
+	];
+	let executors = [
+		("naive", Executor::Naive),
+		("ir", Executor::Ir),
+		("vm", Executor::Vm),
+	];
+
+	for (program, code, samples) in programs {
+		let mut group = c.benchmark_group(program);
+		group.sample_size(samples);
+
+		for (name, executor) in executors {
+			let program = compile_code(code, executor).unwrap();
+
+			group.bench_function(name, |b| {
+				b.iter(|| program.run(empty(), sink()))
+			});
+		}
+
+		group.finish();
+	}
+}
+
+criterion_group!(benches, executors);
+criterion_main!(benches);
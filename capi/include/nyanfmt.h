@@ -0,0 +1,58 @@
+/*
+ * C interface of nyanfmt, the Nyanlang formatter.
+ *
+ * Link against libnyanfmt_capi, built with `cargo build -p nyanfmt-capi`.
+ */
+
+#ifndef NYANFMT_H
+#define NYANFMT_H
+
+#include <stdbool.h>
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Why the last call on the calling thread failed.
+ *
+ * `message` is owned by the library and stays valid until the next call
+ * on the same thread. The span is only set if `has_span` is true.
+ */
+typedef struct nyanfmt_error {
+	const char *message;
+	bool has_span;
+	/* byte offsets in the code, end excluded */
+	size_t start;
+	size_t end;
+	/* one-based, the column counted in characters */
+	size_t line;
+	size_t column;
+} nyanfmt_error;
+
+/*
+ * Formats `len` bytes of UTF-8 code.
+ *
+ * Returns the formatted code, NUL-terminated, to release with
+ * `nyanfmt_string_free`, and stores its length in `out_len` unless it is
+ * NULL. Returns NULL on failure, leaving `out_len` as it is, see
+ * `nyanfmt_last_error`.
+ */
+char *nyanfmt_format(const char *code, size_t len, size_t *out_len);
+
+/* Releases a string returned by the library. Does nothing on NULL. */
+void nyanfmt_string_free(char *string);
+
+/*
+ * Fills `error` with why the last call on the calling thread failed.
+ *
+ * Returns false, leaving `error` untouched, if the last call succeeded.
+ */
+bool nyanfmt_last_error(nyanfmt_error *error);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,148 @@
+//! C interface of the formatter, declared in `include/nyanfmt.h`.
+//!
+//! Functions report failure by returning `NULL`, keeping why they failed
+//! for [`nyanfmt_last_error`] to retrieve, like `errno`.
+
+use std::{
+	cell::RefCell,
+	ffi::{c_char, CString},
+	ptr, slice, str,
+};
+
+use nyanfmt::{format_code, Config, Span};
+
+/// Mirrors `nyanfmt_error` in the header.
+#[repr(C)]
+pub struct NyanfmtError {
+	pub message: *const c_char,
+	pub has_span: bool,
+	pub start: usize,
+	pub end: usize,
+	pub line: usize,
+	pub column: usize,
+}
+
+struct LastError {
+	message: CString,
+	/// span with its line and column
+	span: Option<(Span, (usize, usize))>,
+}
+
+thread_local! {
+	static LAST_ERROR: RefCell<Option<LastError>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(error: Option<LastError>) {
+	LAST_ERROR.with(|last| *last.borrow_mut() = error);
+}
+
+fn fail(code: &str, message: String, span: Option<Span>) {
+	set_last_error(Some(LastError {
+		message: CString::new(message).unwrap_or_default(),
+		span: span.map(|span| (span, span.line_col(code))),
+	}));
+}
+
+/// Formats `len` bytes of UTF-8 code, returning a string to release with
+/// [`nyanfmt_string_free`].
+///
+/// # Safety
+///
+/// `code` must point to `len` readable bytes, and `out_len` must be null
+/// or point to a writable `usize`, which is only written on success.
+#[no_mangle]
+pub unsafe extern "C" fn nyanfmt_format(
+	code: *const c_char,
+	len: usize,
+	out_len: *mut usize,
+) -> *mut c_char {
+	set_last_error(None);
+
+	if code.is_null() {
+		fail(
+			"",
+			String::from("the code is NULL"),
+			None,
+		);
+		return ptr::null_mut();
+	}
+
+	let bytes = slice::from_raw_parts(code.cast::<u8>(), len);
+	let code = match str::from_utf8(bytes) {
+		Ok(code) => code,
+		Err(err) => {
+			let valid = str::from_utf8(&bytes[..err.valid_up_to()]).unwrap();
+			let at = err.valid_up_to();
+			let end = at + err.error_len().unwrap_or(len - at);
+
+			fail(
+				valid,
+				String::from("the code is not valid UTF-8"),
+				Some(Span::new(at, end)),
+			);
+			return ptr::null_mut();
+		},
+	};
+
+	match format_code(code, &Config::default()) {
+		Ok(formatted) => match CString::new(formatted) {
+			Ok(formatted) => {
+				if !out_len.is_null() {
+					*out_len = formatted.as_bytes().len();
+				}
+				formatted.into_raw()
+			},
+			Err(_) => {
+				fail(
+					code,
+					String::from("the formatted code contains a NUL byte"),
+					None,
+				);
+				ptr::null_mut()
+			},
+		},
+		Err(err) => {
+			fail(code, err.to_string(), err.span());
+			ptr::null_mut()
+		},
+	}
+}
+
+/// Releases a string returned by the library.
+///
+/// # Safety
+///
+/// `string` must be null or returned by the library, and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn nyanfmt_string_free(string: *mut c_char) {
+	if !string.is_null() {
+		drop(CString::from_raw(string));
+	}
+}
+
+/// Fills `error` with why the last call on this thread failed, returning
+/// whether it did.
+///
+/// # Safety
+///
+/// `error` must point to a writable `nyanfmt_error`.
+#[no_mangle]
+pub unsafe extern "C" fn nyanfmt_last_error(error: *mut NyanfmtError) -> bool {
+	LAST_ERROR.with(|last| {
+		let last = last.borrow();
+		let Some(last) = last.as_ref() else {
+			return false;
+		};
+		let (span, (line, column)) = last.span.unwrap_or_default();
+
+		*error = NyanfmtError {
+			message: last.message.as_ptr(),
+			has_span: last.span.is_some(),
+			start: span.start,
+			end: span.end,
+			line,
+			column,
+		};
+		true
+	})
+}
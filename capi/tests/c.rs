@@ -0,0 +1,60 @@
+use std::{env, path::PathBuf, process::Command};
+
+/// Directory the library is built to, two levels above the test binary.
+fn target_dir() -> PathBuf {
+	let exe = env::current_exe().unwrap();
+
+	exe.parent()
+		.unwrap()
+		.parent()
+		.unwrap()
+		.to_path_buf()
+}
+
+/// Builds the shared library, which cargo does not build for integration
+/// tests, returning the directory it is in.
+fn build_library() -> PathBuf {
+	let lib = target_dir();
+	let mut cargo = Command::new(env!("CARGO"));
+	cargo.args(["build", "--lib", "-p", env!("CARGO_PKG_NAME")]);
+	if lib.ends_with("release") {
+		cargo.arg("--release");
+	}
+
+	assert!(
+		cargo
+			.status()
+			.expect("Can't run cargo")
+			.success(),
+		"cargo build failed"
+	);
+	lib
+}
+
+#[test]
+fn c_program_must_format_through_header() {
+	let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+	let lib = build_library();
+	let exe = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("format");
+
+	let status = Command::new("cc")
+		.arg(manifest.join("tests/format.c"))
+		.arg("-I")
+		.arg(manifest.join("include"))
+		.arg("-L")
+		.arg(&lib)
+		.arg(format!("-Wl,-rpath,{}", lib.display()))
+		.arg("-lnyanfmt_capi")
+		.arg("-o")
+		.arg(&exe)
+		.status()
+		.expect("Can't run cc");
+	assert!(status.success(), "cc failed");
+
+	let output = Command::new(&exe).output().unwrap();
+	assert!(
+		output.status.success(),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+}
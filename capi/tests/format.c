@@ -0,0 +1,50 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "nyanfmt.h"
+
+#define CHECK(cond)                                                      \
+	do {                                                                 \
+		if (!(cond)) {                                                   \
+			fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,      \
+			        __LINE__, #cond);                                    \
+			return 1;                                                    \
+		}                                                                \
+	} while (0)
+
+int main(void) {
+	nyanfmt_error error;
+	size_t len = 0;
+
+	const char *code = "\"주석\"\n냥냥 냥~?";
+	char *formatted = nyanfmt_format(code, strlen(code), &len);
+	CHECK(formatted != NULL);
+	CHECK(strcmp(formatted, "\"주석\"\n냥냥냥~?\n") == 0);
+	CHECK(len == strlen(formatted));
+	CHECK(!nyanfmt_last_error(&error));
+	nyanfmt_string_free(formatted);
+
+	const char *invalid = "냥냥\n냥a";
+	CHECK(nyanfmt_format(invalid, strlen(invalid), NULL) == NULL);
+	CHECK(nyanfmt_last_error(&error));
+	CHECK(strcmp(error.message, "failed to tokenize the code") == 0);
+	CHECK(error.has_span);
+	CHECK(error.start == 10 && error.end == 11);
+	CHECK(error.line == 2 && error.column == 2);
+
+	const char bad_utf8[] = {'\xeb', '\xa5', '?', '\0'};
+	CHECK(nyanfmt_format(bad_utf8, 3, NULL) == NULL);
+	CHECK(nyanfmt_last_error(&error));
+	CHECK(error.has_span && error.start == 0 && error.end == 2);
+
+	const char with_nul[] = "\"a\0b\" 냥";
+	len = 42;
+	CHECK(nyanfmt_format(with_nul, sizeof with_nul - 1, &len) == NULL);
+	CHECK(len == 42);
+	CHECK(nyanfmt_last_error(&error));
+	CHECK(strcmp(error.message, "the formatted code contains a NUL byte") ==
+	      0);
+
+	nyanfmt_string_free(NULL);
+	return 0;
+}
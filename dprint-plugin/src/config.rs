@@ -0,0 +1,152 @@
+use nyanfmt::{
+	CommentPlacement, CommentWhitespace, Config, NewLine, UnknownChars,
+};
+use serde_json::{json, Map, Value};
+
+pub type ConfigMap = Map<String, Value>;
+
+const NEW_LINE_KIND: &str = "newLineKind";
+const UNKNOWN_CHARS: &str = "unknownChars";
+const COMMENT_PLACEMENT: &str = "commentPlacement";
+const COMMENT_WHITESPACE: &str = "commentWhitespace";
+/// Properties dprint handles for every plugin.
+const RESERVED: [&str; 2] = ["associations", "locked"];
+
+fn diagnostic(property: &str, message: &str) -> Value {
+	json!({
+		"propertyName": property,
+		"message": message,
+	})
+}
+
+/// The host system is not known inside Wasm, so `system` follows the code
+/// like `auto` does.
+fn new_line(kind: &Value) -> Option<NewLine> {
+	match kind.as_str()? {
+		"lf" => Some(NewLine::Lf),
+		"crlf" => Some(NewLine::Crlf),
+		"auto" | "system" => Some(NewLine::Auto),
+		_ => None,
+	}
+}
+
+fn unknown_chars(policy: &Value) -> Option<UnknownChars> {
+	match policy.as_str()? {
+		"error" => Some(UnknownChars::Error),
+		"strip" => Some(UnknownChars::Strip),
+		"comment" => Some(UnknownChars::Comment),
+		_ => None,
+	}
+}
+
+fn comment_placement(placement: &Value) -> Option<CommentPlacement> {
+	match placement.as_str()? {
+		"inline" => Some(CommentPlacement::Inline),
+		"above" => Some(CommentPlacement::Above),
+		_ => None,
+	}
+}
+
+fn comment_whitespace(whitespace: &Value) -> Option<CommentWhitespace> {
+	match whitespace.as_str()? {
+		"keep" => Some(CommentWhitespace::Keep),
+		"trim" => Some(CommentWhitespace::Trim),
+		"normalize" => Some(CommentWhitespace::Normalize),
+		_ => None,
+	}
+}
+
+/// Maps the plugin configuration onto [`Config`], falling back to the
+/// global configuration for the properties dprint shares between plugins.
+///
+/// Also returns the problems found, as dprint configuration diagnostics.
+pub fn resolve(plugin: &ConfigMap, global: &ConfigMap) -> (Config, Vec<Value>) {
+	let mut config = Config::default();
+	let mut diagnostics = vec![];
+
+	if let Some(kind) = plugin
+		.get(NEW_LINE_KIND)
+		.or(global.get(NEW_LINE_KIND))
+	{
+		match new_line(kind) {
+			Some(new_line) => config.format.new_line = new_line,
+			None => diagnostics.push(diagnostic(
+				NEW_LINE_KIND,
+				"Expected one of \"auto\", \"lf\", \"crlf\" or \"system\"",
+			)),
+		}
+	}
+
+	if let Some(policy) = plugin.get(UNKNOWN_CHARS) {
+		match unknown_chars(policy) {
+			Some(unknown_chars) => config.format.unknown_chars = unknown_chars,
+			None => diagnostics.push(diagnostic(
+				UNKNOWN_CHARS,
+				"Expected one of \"error\", \"strip\" or \"comment\"",
+			)),
+		}
+	}
+
+	if let Some(placement) = plugin.get(COMMENT_PLACEMENT) {
+		match comment_placement(placement) {
+			Some(placement) => config.format.comment_placement = placement,
+			None => diagnostics.push(diagnostic(
+				COMMENT_PLACEMENT,
+				"Expected one of \"inline\" or \"above\"",
+			)),
+		}
+	}
+
+	if let Some(whitespace) = plugin.get(COMMENT_WHITESPACE) {
+		match comment_whitespace(whitespace) {
+			Some(whitespace) => config.format.comment_whitespace = whitespace,
+			None => diagnostics.push(diagnostic(
+				COMMENT_WHITESPACE,
+				"Expected one of \"keep\", \"trim\" or \"normalize\"",
+			)),
+		}
+	}
+
+	for property in plugin.keys().filter(|property| {
+		![
+			NEW_LINE_KIND,
+			UNKNOWN_CHARS,
+			COMMENT_PLACEMENT,
+			COMMENT_WHITESPACE,
+		]
+		.contains(&property.as_str())
+			&& !RESERVED.contains(&property.as_str())
+	}) {
+		diagnostics.push(diagnostic(
+			property,
+			"Unknown property in configuration",
+		));
+	}
+
+	(config, diagnostics)
+}
+
+/// The configuration as dprint shows it, with the plugin's property names.
+pub fn to_json(config: &Config) -> Value {
+	json!({
+		NEW_LINE_KIND: match config.format.new_line {
+			NewLine::Lf => "lf",
+			NewLine::Crlf => "crlf",
+			NewLine::Auto => "auto",
+		},
+		UNKNOWN_CHARS: match config.format.unknown_chars {
+			UnknownChars::Error => "error",
+			UnknownChars::Strip => "strip",
+			UnknownChars::Comment => "comment",
+		},
+		COMMENT_PLACEMENT: match config.format.comment_placement {
+			CommentPlacement::Inline => "inline",
+			CommentPlacement::Above => "above",
+		},
+		COMMENT_WHITESPACE: match config.format.comment_whitespace {
+			CommentWhitespace::Keep => "keep",
+			CommentWhitespace::Trim => "trim",
+			CommentWhitespace::Normalize => "normalize",
+		},
+	})
+}
@@ -0,0 +1,250 @@
+//! [dprint](https://dprint.dev) plugin formatting `.nyan` files with
+//! nyanfmt, implementing the exports of dprint's Wasm plugin schema,
+//! version 4.
+//!
+//! The host and the plugin exchange data through a shared buffer. The host
+//! asks for a buffer of some size with [`clear_shared_bytes`] and fills it
+//! before calling an export reading from it, while the exports writing to
+//! it return the length of what they wrote, to be read from
+//! [`get_shared_bytes_ptr`].
+
+mod config;
+
+use std::{cell::RefCell, collections::HashMap, mem};
+
+use config::{resolve, to_json, ConfigMap};
+use nyanfmt::{format_code, Config, Error};
+use serde_json::{json, Value};
+
+/// [`format`] result for code that is already formatted
+const NO_CHANGE: u32 = 0;
+/// [`format`] result for code whose formatted text is ready
+const CHANGE: u32 = 1;
+/// [`format`] result for code that could not be formatted
+const ERROR: u32 = 2;
+
+struct Registered {
+	plugin: ConfigMap,
+	global: ConfigMap,
+}
+
+#[derive(Default)]
+struct State {
+	shared: Vec<u8>,
+	configs: HashMap<u32, Registered>,
+	/// plugin configuration overriding the registered one for the next
+	/// file only
+	override_config: ConfigMap,
+	formatted: String,
+	error: String,
+}
+
+impl State {
+	fn resolve(&self, config_id: u32) -> (Config, Vec<Value>) {
+		match self.configs.get(&config_id) {
+			Some(Registered { plugin, global }) => resolve(plugin, global),
+			None => resolve(&ConfigMap::new(), &ConfigMap::new()),
+		}
+	}
+}
+
+thread_local! {
+	static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+fn with<T>(f: impl FnOnce(&mut State) -> T) -> T {
+	STATE.with(|state| f(&mut state.borrow_mut()))
+}
+
+/// Takes the bytes the host wrote to the shared buffer.
+fn take() -> Vec<u8> {
+	with(|state| mem::take(&mut state.shared))
+}
+
+/// Fills the shared buffer, returning the length written.
+fn share(bytes: impl Into<Vec<u8>>) -> usize {
+	with(|state| {
+		state.shared = bytes.into();
+		state.shared.len()
+	})
+}
+
+fn take_json() -> ConfigMap {
+	serde_json::from_slice(&take()).unwrap_or_default()
+}
+
+fn share_json(value: &Value) -> usize {
+	share(value.to_string())
+}
+
+fn describe(code: &str, err: Error) -> String {
+	match err {
+		Error::Lex { at }
+		| Error::UnterminatedComment { at }
+		| Error::Parse { at } => {
+			let (line, col) = at.line_col(code);
+			format!("{err} at line {line}, column {col}")
+		},
+		err => err.to_string(),
+	}
+}
+
+#[no_mangle]
+pub extern "C" fn dprint_plugin_version_4() -> u32 {
+	4
+}
+
+#[no_mangle]
+pub extern "C" fn get_shared_bytes_ptr() -> *const u8 {
+	with(|state| state.shared.as_ptr())
+}
+
+/// Replaces the shared buffer with `size` zeroed bytes, returning where
+/// the host should write to.
+#[no_mangle]
+pub extern "C" fn clear_shared_bytes(size: usize) -> *mut u8 {
+	with(|state| {
+		state.shared = vec![0; size];
+		state.shared.as_mut_ptr()
+	})
+}
+
+#[no_mangle]
+pub extern "C" fn get_plugin_info() -> usize {
+	share_json(&json!({
+		"name": env!("CARGO_PKG_NAME"),
+		"version": env!("CARGO_PKG_VERSION"),
+		"configKey": "nyanfmt",
+		"helpUrl": "https://github.com/abiriadev/nyanfmt",
+		"configSchemaUrl": "",
+	}))
+}
+
+#[no_mangle]
+pub extern "C" fn get_license_text() -> usize {
+	share("See https://github.com/abiriadev/nyanfmt for the license.")
+}
+
+/// Registers the configuration in the shared buffer, given as
+/// `{ "plugin": {..}, "global": {..} }`.
+#[no_mangle]
+pub extern "C" fn register_config(config_id: u32) {
+	let mut config = take_json();
+	let mut part = |key| match config.remove(key) {
+		Some(Value::Object(map)) => map,
+		_ => ConfigMap::new(),
+	};
+	let registered = Registered {
+		plugin: part("plugin"),
+		global: part("global"),
+	};
+
+	with(|state| {
+		state
+			.configs
+			.insert(config_id, registered)
+	});
+}
+
+#[no_mangle]
+pub extern "C" fn release_config(config_id: u32) {
+	with(|state| state.configs.remove(&config_id));
+}
+
+#[no_mangle]
+pub extern "C" fn get_config_diagnostics(config_id: u32) -> usize {
+	let (_, diagnostics) = with(|state| state.resolve(config_id));
+
+	share_json(&Value::Array(diagnostics))
+}
+
+#[no_mangle]
+pub extern "C" fn get_resolved_config(config_id: u32) -> usize {
+	let (config, _) = with(|state| state.resolve(config_id));
+
+	share_json(&to_json(&config))
+}
+
+#[no_mangle]
+pub extern "C" fn get_config_file_matching(_config_id: u32) -> usize {
+	share_json(&json!({
+		"fileExtensions": ["nyan"],
+		"fileNames": [],
+	}))
+}
+
+/// Takes the path of the file to format next. Every file is formatted the
+/// same way, so it is not used.
+#[no_mangle]
+pub extern "C" fn set_file_path() {
+	take();
+}
+
+/// Takes the plugin configuration overriding the registered one for the
+/// next file.
+#[no_mangle]
+pub extern "C" fn set_override_config() {
+	let override_config = take_json();
+
+	with(|state| state.override_config = override_config);
+}
+
+/// Formats the code in the shared buffer, returning whether it changed.
+///
+/// The formatted text is then read with [`get_formatted_text`], or the
+/// reason it could not be formatted with [`get_error_text`].
+#[no_mangle]
+pub extern "C" fn format(config_id: u32) -> u32 {
+	let code = String::from_utf8(take());
+
+	with(|state| {
+		let override_config = mem::take(&mut state.override_config);
+		let (config, _) = match state.configs.get(&config_id) {
+			Some(Registered { plugin, global }) => {
+				let mut plugin = plugin.clone();
+				plugin.extend(override_config);
+				resolve(&plugin, global)
+			},
+			None => resolve(&override_config, &ConfigMap::new()),
+		};
+
+		let result = match code {
+			Ok(code) => format_code(&code, &config)
+				.map(|formatted| (formatted != code, formatted))
+				.map_err(|err| describe(&code, err)),
+			Err(_) => Err(String::from(
+				"the file is not valid UTF-8",
+			)),
+		};
+
+		match result {
+			Ok((false, _)) => NO_CHANGE,
+			Ok((true, formatted)) => {
+				state.formatted = formatted;
+				CHANGE
+			},
+			Err(err) => {
+				state.error = err;
+				ERROR
+			},
+		}
+	})
+}
+
+#[no_mangle]
+pub extern "C" fn get_formatted_text() -> usize {
+	let formatted = with(|state| mem::take(&mut state.formatted));
+
+	share(formatted)
+}
+
+#[no_mangle]
+pub extern "C" fn get_error_text() -> usize {
+	let error = with(|state| mem::take(&mut state.error));
+
+	share(error)
+}
+
+#[cfg(test)]
+#[path = "lib.spec.rs"]
+mod tests;
@@ -0,0 +1,228 @@
+use std::{ptr, slice};
+
+use super::*;
+use pretty_assertions::assert_eq;
+
+fn send(bytes: &[u8]) {
+	let ptr = clear_shared_bytes(bytes.len());
+	unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len()) }
+}
+
+fn receive(len: usize) -> String {
+	let bytes = unsafe { slice::from_raw_parts(get_shared_bytes_ptr(), len) };
+
+	String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+fn register(config_id: u32, config: Value) {
+	send(config.to_string().as_bytes());
+	register_config(config_id);
+}
+
+fn format_file(config_id: u32, code: &str) -> (u32, String) {
+	send(b"/project/a.nyan");
+	set_file_path();
+	send(code.as_bytes());
+
+	match format(config_id) {
+		CHANGE => (CHANGE, receive(get_formatted_text())),
+		ERROR => (ERROR, receive(get_error_text())),
+		result => (result, String::new()),
+	}
+}
+
+fn receive_json(len: usize) -> Value {
+	serde_json::from_str(&receive(len)).unwrap()
+}
+
+#[test]
+fn plugin_info_must_name_config_key() {
+	assert_eq!(
+		(
+			dprint_plugin_version_4(),
+			receive_json(get_plugin_info())["configKey"].clone()
+		),
+		(4, json!("nyanfmt"))
+	)
+}
+
+#[test]
+fn nyan_files_must_be_matched() {
+	register(1, json!({ "plugin": {}, "global": {} }));
+
+	assert_eq!(
+		receive_json(get_config_file_matching(1)),
+		json!({ "fileExtensions": ["nyan"], "fileNames": [] })
+	)
+}
+
+#[test]
+fn unformatted_code_must_be_formatted() {
+	register(1, json!({ "plugin": {}, "global": {} }));
+
+	assert_eq!(
+		format_file(1, "\"주석\"\n냥냥 냥~?"),
+		(
+			CHANGE,
+			String::from("\"주석\"\n냥냥냥~?\n")
+		)
+	)
+}
+
+#[test]
+fn formatted_code_must_not_change() {
+	register(1, json!({ "plugin": {}, "global": {} }));
+
+	assert_eq!(
+		format_file(1, "\"주석\"\n냥냥냥~?\n"),
+		(NO_CHANGE, String::new())
+	)
+}
+
+#[test]
+fn invalid_code_must_report_where_it_failed() {
+	register(1, json!({ "plugin": {}, "global": {} }));
+
+	assert_eq!(
+		format_file(1, "냥\n냥a"),
+		(
+			ERROR,
+			String::from("failed to tokenize the code at line 2, column 2")
+		)
+	)
+}
+
+#[test]
+fn new_line_kind_must_fall_back_to_global_config() {
+	register(
+		1,
+		json!({ "plugin": {}, "global": { "newLineKind": "crlf" } }),
+	);
+	register(
+		2,
+		json!({
+			"plugin": { "newLineKind": "lf" },
+			"global": { "newLineKind": "crlf" },
+		}),
+	);
+
+	assert_eq!(
+		[
+			receive_json(get_resolved_config(1)),
+			receive_json(get_resolved_config(2)),
+		],
+		[
+			json!({
+				"newLineKind": "crlf",
+				"unknownChars": "error",
+				"commentPlacement": "inline",
+				"commentWhitespace": "keep",
+			}),
+			json!({
+				"newLineKind": "lf",
+				"unknownChars": "error",
+				"commentPlacement": "inline",
+				"commentWhitespace": "keep",
+			})
+		]
+	);
+	assert_eq!(
+		format_file(1, "냥\n냐"),
+		(CHANGE, String::from("냥\r\n냐\r\n"))
+	)
+}
+
+#[test]
+fn unknown_properties_must_be_diagnosed() {
+	register(
+		1,
+		json!({
+			"plugin": { "lineWidth": 80, "locked": true, "newLineKind": "cr" },
+			"global": {},
+		}),
+	);
+
+	assert_eq!(
+		receive_json(get_config_diagnostics(1)),
+		json!([
+			{
+				"propertyName": "newLineKind",
+				"message": "Expected one of \"auto\", \"lf\", \"crlf\" or \"system\"",
+			},
+			{
+				"propertyName": "lineWidth",
+				"message": "Unknown property in configuration",
+			},
+		])
+	)
+}
+
+#[test]
+fn override_config_must_apply_to_next_file_only() {
+	register(1, json!({ "plugin": {}, "global": {} }));
+	send(
+		json!({ "newLineKind": "crlf" })
+			.to_string()
+			.as_bytes(),
+	);
+	set_override_config();
+
+	assert_eq!(
+		[format_file(1, "냥\n냐"), format_file(1, "냥\n냐")],
+		[
+			(CHANGE, String::from("냥\r\n냐\r\n")),
+			(CHANGE, String::from("냥\n냐\n")),
+		]
+	)
+}
+
+#[test]
+fn released_config_must_fall_back_to_defaults() {
+	register(
+		1,
+		json!({ "plugin": { "newLineKind": "crlf" }, "global": {} }),
+	);
+	release_config(1);
+
+	assert_eq!(
+		receive_json(get_resolved_config(1)),
+		json!({
+			"newLineKind": "lf",
+			"unknownChars": "error",
+			"commentPlacement": "inline",
+			"commentWhitespace": "keep",
+		})
+	)
+}
+
+#[test]
+fn unknown_chars_must_follow_plugin_config() {
+	register(
+		1,
+		json!({ "plugin": { "unknownChars": "strip" }, "global": {} }),
+	);
+
+	assert_eq!(
+		format_file(1, "냥 meow 냐"),
+		(CHANGE, String::from("냥냐\n"))
+	)
+}
+
+#[test]
+fn comment_options_must_follow_plugin_config() {
+	register(
+		1,
+		json!({
+			"plugin": {
+				"commentPlacement": "above",
+				"commentWhitespace": "normalize",
+			},
+			"global": {},
+		}),
+	);
+
+	assert_eq!(
+		format_file(1, "냥 \"  a  b \" 냐"),
+		(CHANGE, String::from("\"a b\"\n냥냐\n"))
+	)
+}
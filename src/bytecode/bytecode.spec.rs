@@ -0,0 +1,52 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn assemble_must_encode_operands_in_little_endian() {
+	assert_eq!(
+		assemble(&[
+			Op::Add(3),
+			Op::Move(-2),
+			Op::Set(0),
+			Op::MulAdd {
+				offset: 1,
+				factor: 4
+			},
+			Op::Out,
+			Op::In,
+		]),
+		[
+			ADD, 3, MOVE, 0xfe, 0xff, 0xff, 0xff, SET, 0, MUL_ADD, 1, 0, 0, 0,
+			4, OUT, IN,
+		]
+	)
+}
+
+#[test]
+fn assemble_must_resolve_jumps_to_addresses_after_the_matching_jump() {
+	assert_eq!(
+		assemble(&[
+			Op::JumpRight(3),
+			Op::Out,
+			Op::Add(1),
+			Op::JumpLeft(0),
+			Op::In,
+		]),
+		[
+			JUMP_ZERO,
+			13,
+			0,
+			0,
+			0,
+			OUT,
+			ADD,
+			1,
+			JUMP_NONZERO,
+			5,
+			0,
+			0,
+			0,
+			IN,
+		]
+	)
+}
@@ -0,0 +1,71 @@
+mod vm;
+
+pub use vm::run;
+
+use crate::ir::Op;
+
+// each opcode is a single byte, followed by its operands in little endian
+pub const ADD: u8 = 0x00; // u8
+pub const MOVE: u8 = 0x01; // i32
+pub const SET: u8 = 0x02; // u8
+pub const MUL_ADD: u8 = 0x03; // i32 offset, u8 factor
+pub const OUT: u8 = 0x04;
+pub const IN: u8 = 0x05;
+pub const JUMP_ZERO: u8 = 0x06; // u32 address
+pub const JUMP_NONZERO: u8 = 0x07; // u32 address
+
+fn size(op: &Op) -> usize {
+	match op {
+		Op::Add(_) | Op::Set(_) => 2,
+		Op::Move(_) | Op::JumpRight(_) | Op::JumpLeft(_) => 5,
+		Op::MulAdd { .. } => 6,
+		Op::Out | Op::In => 1,
+	}
+}
+
+/// Encodes linked instructions into bytecode.
+///
+/// Jumps land right after their matching jump, so the VM never has to
+/// execute the check it just jumped over.
+pub fn assemble(ops: &[Op]) -> Vec<u8> {
+	let addresses = ops
+		.iter()
+		.scan(0, |address, op| {
+			*address += size(op);
+			Some(*address)
+		})
+		.collect::<Vec<_>>();
+	let mut code = Vec::with_capacity(addresses.last().copied().unwrap_or(0));
+
+	for op in ops {
+		match *op {
+			Op::Add(n) => code.extend([ADD, n]),
+			Op::Move(n) => {
+				code.push(MOVE);
+				code.extend((n as i32).to_le_bytes());
+			},
+			Op::Set(n) => code.extend([SET, n]),
+			Op::MulAdd { offset, factor } => {
+				code.push(MUL_ADD);
+				code.extend((offset as i32).to_le_bytes());
+				code.push(factor);
+			},
+			Op::Out => code.push(OUT),
+			Op::In => code.push(IN),
+			Op::JumpRight(end) => {
+				code.push(JUMP_ZERO);
+				code.extend((addresses[end] as u32).to_le_bytes());
+			},
+			Op::JumpLeft(start) => {
+				code.push(JUMP_NONZERO);
+				code.extend((addresses[start] as u32).to_le_bytes());
+			},
+		}
+	}
+
+	code
+}
+
+#[cfg(test)]
+#[path = "bytecode.spec.rs"]
+mod tests;
@@ -0,0 +1,59 @@
+use std::io::{Read, Write};
+
+use super::*;
+use crate::{
+	interpreter::{read_byte, Tape},
+	Error,
+};
+
+#[inline]
+fn operand<const N: usize>(code: &[u8], pc: &mut usize) -> [u8; N] {
+	let bytes = code[*pc..*pc + N].try_into().unwrap();
+	*pc += N;
+
+	bytes
+}
+
+/// Executes bytecode produced by [`assemble`] on a fresh tape.
+pub fn run(
+	code: &[u8],
+	mut input: impl Read,
+	mut output: impl Write,
+) -> Result<(), Error> {
+	let mut tape = Tape::new();
+	let mut pc = 0;
+
+	while let Some(&opcode) = code.get(pc) {
+		pc += 1;
+
+		match opcode {
+			ADD => tape.add(operand::<1>(code, &mut pc)[0]),
+			MOVE => {
+				tape.shift(i32::from_le_bytes(operand(code, &mut pc)) as isize)?
+			},
+			SET => tape.set(operand::<1>(code, &mut pc)[0]),
+			MUL_ADD => {
+				let offset = i32::from_le_bytes(operand(code, &mut pc));
+				let [factor] = operand(code, &mut pc);
+
+				tape.mul_add(offset as isize, factor)?
+			},
+			OUT => output.write_all(&[tape.get()])?,
+			IN => tape.set(read_byte(&mut input)?),
+			JUMP_ZERO | JUMP_NONZERO => {
+				let address = u32::from_le_bytes(operand(code, &mut pc));
+
+				if (tape.get() == 0) == (opcode == JUMP_ZERO) {
+					pc = address as usize;
+				}
+			},
+			_ => unreachable!("invalid opcode {opcode:#04x}"),
+		}
+	}
+
+	Ok(output.flush()?)
+}
+
+#[cfg(test)]
+#[path = "vm.spec.rs"]
+mod tests;
@@ -0,0 +1,75 @@
+use super::*;
+use crate::{ir::compile, parse_code};
+use pretty_assertions::assert_eq;
+
+fn run_with(code: &str, input: &[u8]) -> Result<Vec<u8>, Error> {
+	let mut output = vec![];
+
+	run(
+		&assemble(&compile(&parse_code(code).unwrap())?),
+		input,
+		&mut output,
+	)?;
+
+	Ok(output)
+}
+
+#[test]
+fn run_hello_world() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/hello.nyan"),
+			b""
+		),
+		Ok(b"Hello World!\n".to_vec())
+	)
+}
+
+#[test]
+fn run_reverse() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/reverse.nyan"),
+			b"nyan"
+		),
+		Ok(b"nayn".to_vec())
+	)
+}
+
+#[test]
+fn run_sierpinski() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/sierpinski.nyan"),
+			b""
+		),
+		Ok(include_bytes!("../../samples/sierpinski.out").to_vec())
+	)
+}
+
+#[test]
+fn run_mandelbrot() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/mandelbrot.nyan"),
+			b""
+		),
+		Ok(include_bytes!("../../samples/mandelbrot.out").to_vec())
+	)
+}
+
+#[test]
+fn run_multiplication() {
+	assert_eq!(
+		run_with("냥냥냥~? 냥냥냥냥!냐-?.", b""),
+		Ok(vec![12])
+	)
+}
+
+#[test]
+fn must_fail_when_pointer_moves_left_of_first_cell() {
+	assert_eq!(
+		run_with("?!!", b""),
+		Err(Error::PointerUnderflow)
+	)
+}
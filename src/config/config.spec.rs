@@ -0,0 +1,154 @@
+use super::*;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn empty_config_must_keep_default_severities() {
+	let config = Config::from_toml("").unwrap();
+
+	assert_eq!(
+		Rule::ALL.map(|rule| config.severity(rule)),
+		Rule::ALL.map(|rule| Some(rule.severity()))
+	)
+}
+
+#[test]
+fn rule_levels_must_be_read_by_id() {
+	let config = Config::from_toml(indoc! {r#"
+		[lint]
+		debug-symbol = "off"
+		cancel-pair = "error"
+		unmatched-jump = "warning"
+	"#})
+	.unwrap();
+
+	assert_eq!(
+		[
+			config.severity(Rule::DebugSymbol),
+			config.severity(Rule::CancelPair),
+			config.severity(Rule::UnmatchedJump),
+			config.severity(Rule::EmptyLoop),
+		],
+		[
+			None,
+			Some(Severity::Error),
+			Some(Severity::Warning),
+			Some(Severity::Warning),
+		]
+	)
+}
+
+#[test]
+fn unknown_rules_must_be_rejected() {
+	assert!(matches!(
+		Config::from_toml("[lint]\nno-such-rule = \"off\""),
+		Err(Error::Config(_))
+	))
+}
+
+#[test]
+fn unknown_levels_must_be_rejected() {
+	assert!(matches!(
+		Config::from_toml("[lint]\ncancel-pair = \"loud\""),
+		Err(Error::Config(_))
+	))
+}
+
+#[test]
+fn closest_config_file_must_be_discovered() {
+	let dir = std::env::temp_dir().join("nyanfmt-config-discover");
+	let sub = dir.join("sub");
+	std::fs::create_dir_all(&sub).unwrap();
+	std::fs::write(
+		dir.join(Config::FILE_NAME),
+		"[lint]\ncancel-pair = \"off\"",
+	)
+	.unwrap();
+	std::fs::write(sub.join("a.nyan"), "냥").unwrap();
+
+	assert_eq!(
+		Config::discover(&sub.join("a.nyan"))
+			.map(|c| c.severity(Rule::CancelPair)),
+		Ok(None)
+	)
+}
+
+#[test]
+fn new_line_must_be_read_from_format_section() {
+	assert_eq!(
+		Config::from_toml("[format]\nnew-line = \"crlf\"")
+			.map(|c| c.format.new_line),
+		Ok(NewLine::Crlf)
+	)
+}
+
+#[test]
+fn unknown_chars_must_be_read_from_format_section() {
+	assert_eq!(
+		Config::from_toml("[format]\nunknown-chars = \"comment\"")
+			.map(|c| c.format.unknown_chars),
+		Ok(UnknownChars::Comment)
+	)
+}
+
+#[test]
+fn auto_new_line_must_follow_first_line_break() {
+	assert_eq!(
+		[
+			NewLine::Auto.resolve(
+				"냥\r\n냐\n",
+				&[Span::new(0, 3), Span::new(5, 8)]
+			),
+			NewLine::Auto.resolve(
+				"냥\n냐\r\n",
+				&[Span::new(0, 3), Span::new(4, 7)]
+			),
+			NewLine::Auto.resolve("냥", &[Span::new(0, 3)]),
+			NewLine::Auto.resolve(" \r\n", &[]),
+		],
+		["\r\n", "\n", "\n", "\r\n"]
+	)
+}
+
+#[test]
+fn auto_new_line_must_skip_line_breaks_in_comments() {
+	assert_eq!(
+		NewLine::Auto.resolve(
+			"\"a\nb\"\r\n냥",
+			&[Span::new(0, 5), Span::new(7, 10)]
+		),
+		"\r\n"
+	)
+}
+
+#[test]
+fn comment_options_must_be_read_from_format_section() {
+	assert_eq!(
+		Config::from_toml(indoc! {r#"
+			[format]
+			comment-placement = "above"
+			comment-whitespace = "trim"
+		"#})
+		.map(|c| (
+			c.format.comment_placement,
+			c.format.comment_whitespace
+		)),
+		Ok((
+			CommentPlacement::Above,
+			CommentWhitespace::Trim
+		))
+	)
+}
+
+#[test]
+fn comment_whitespace_must_be_trimmed_or_normalized() {
+	assert_eq!(
+		[
+			CommentWhitespace::Keep,
+			CommentWhitespace::Trim,
+			CommentWhitespace::Normalize,
+		]
+		.map(|whitespace| whitespace.apply("\n a \t b \r\n  c \n")),
+		["\n a \t b \r\n  c \n", "a \t b \r\n  c", "a b\nc"]
+	)
+}
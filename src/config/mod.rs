@@ -0,0 +1,190 @@
+use std::{collections::HashMap, fs::read_to_string, path::Path};
+
+use serde::Deserialize;
+
+use crate::{Error, Rule, Severity, Span};
+
+/// How a lint rule is reported.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Level {
+	/// the rule does not run
+	Off,
+	Warning,
+	Error,
+}
+
+impl Level {
+	pub fn severity(&self) -> Option<Severity> {
+		match self {
+			Self::Off => None,
+			Self::Warning => Some(Severity::Warning),
+			Self::Error => Some(Severity::Error),
+		}
+	}
+}
+
+/// Line break written between the lines of the formatted code.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum NewLine {
+	#[default]
+	Lf,
+	Crlf,
+	/// the line break the code uses first between its tokens, or `Lf` if
+	/// it has none
+	Auto,
+}
+
+impl NewLine {
+	/// Line break to write in the code, given the span of every token
+	/// that is not a line break, so that the line breaks inside multi-line
+	/// comments are not taken for those between the lines.
+	pub fn resolve(&self, code: &str, spans: &[Span]) -> &'static str {
+		match self {
+			Self::Lf => "\n",
+			Self::Crlf => "\r\n",
+			Self::Auto => {
+				let ends = [0].into_iter().chain(spans.iter().map(|s| s.end));
+				let starts = spans
+					.iter()
+					.map(|s| s.start)
+					.chain([code.len()]);
+
+				match ends.zip(starts).find_map(|(start, end)| {
+					code[start..end]
+						.find('\n')
+						.map(|i| start + i)
+				}) {
+					Some(i) if code[..i].ends_with('\r') => "\r\n",
+					_ => "\n",
+				}
+			},
+		}
+	}
+}
+
+/// What becomes of the characters that are not part of any token.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnknownChars {
+	/// the code fails to tokenize
+	#[default]
+	Error,
+	/// they are removed from the code
+	Strip,
+	/// every run of them on a line is kept as a comment
+	Comment,
+}
+
+/// Where the comments following a word on its line are written.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommentPlacement {
+	/// on the line of the word, after it
+	#[default]
+	Inline,
+	/// on their own lines, above the sentence of the word
+	Above,
+}
+
+/// What becomes of the whitespace inside comments. The line breaks of
+/// comments spanning several lines are kept, but those around them go
+/// with the rest of the whitespace when trimming.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommentWhitespace {
+	/// it is kept as it is
+	#[default]
+	Keep,
+	/// it is removed from the start and the end of the comment
+	Trim,
+	/// it is trimmed, and every line of the comment is trimmed with its
+	/// runs of spaces and tabs turned into a single space
+	Normalize,
+}
+
+impl CommentWhitespace {
+	pub fn apply(&self, comment: &str) -> String {
+		match self {
+			Self::Keep => comment.to_string(),
+			Self::Trim => comment.trim().to_string(),
+			Self::Normalize => comment
+				.trim()
+				.lines()
+				.map(|line| {
+					line.split_whitespace()
+						.collect::<Vec<_>>()
+						.join(" ")
+				})
+				.collect::<Vec<_>>()
+				.join("\n"),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FormatConfig {
+	pub new_line: NewLine,
+	pub unknown_chars: UnknownChars,
+	pub comment_placement: CommentPlacement,
+	pub comment_whitespace: CommentWhitespace,
+}
+
+/// Settings read from `nyanfmt.toml`.
+///
+/// ```toml
+/// [format]
+/// new-line = "crlf"
+/// unknown-chars = "strip"
+/// comment-placement = "above"
+/// comment-whitespace = "normalize"
+///
+/// [lint]
+/// debug-symbol = "off"
+/// cancel-pair = "error"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+	pub format: FormatConfig,
+	/// level of the lint rules, by their id
+	pub lint: HashMap<Rule, Level>,
+}
+
+impl Config {
+	pub const FILE_NAME: &'static str = "nyanfmt.toml";
+
+	pub fn from_toml(toml: &str) -> Result<Self, Error> {
+		toml::from_str(toml).map_err(|err| Error::Config(err.message().into()))
+	}
+
+	/// Reads the `nyanfmt.toml` closest to the file, in its directory or
+	/// any of the parent directories, if there is one.
+	pub fn discover(file: &Path) -> Result<Self, Error> {
+		let file = file.canonicalize()?;
+		let Some(path) = file
+			.ancestors()
+			.skip(1)
+			.map(|dir| dir.join(Self::FILE_NAME))
+			.find(|path| path.is_file())
+		else {
+			return Ok(Self::default());
+		};
+
+		Self::from_toml(&read_to_string(path)?)
+	}
+
+	/// Severity of the rule's findings, or `None` if it is turned off.
+	pub fn severity(&self, rule: Rule) -> Option<Severity> {
+		match self.lint.get(&rule) {
+			Some(level) => level.severity(),
+			None => Some(rule.severity()),
+		}
+	}
+}
+
+#[cfg(test)]
+#[path = "config.spec.rs"]
+mod tests;
@@ -0,0 +1,98 @@
+use std::{
+	fmt::{self, Display, Formatter},
+	io,
+};
+
+use crate::Span;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+	/// the code contains a character that is not part of a nyanlang token
+	Lex { at: Span },
+	/// the `"` at this span opens a comment that is never closed
+	UnterminatedComment { at: Span },
+	/// the token stream does not form a valid nyanlang program, starting
+	/// from the token at this span
+	Parse { at: Span },
+	/// a `~` is never closed by a matching `-`
+	UnmatchedJumpRight,
+	/// a `-` has no preceding `~` to jump back to
+	UnmatchedJumpLeft,
+	/// the pointer moved to the left of the first cell
+	PointerUnderflow,
+	/// reading the input or writing the output failed
+	Io(io::ErrorKind),
+	/// formatting changed the tokens of the code, starting at these spans
+	Diverged {
+		input: Option<Span>,
+		output: Option<Span>,
+	},
+	/// formatting the formatted code again changed it at this byte offset
+	NotIdempotent { at: usize },
+	/// the configuration file is invalid
+	Config(String),
+	/// the language server client broke the protocol
+	Protocol(String),
+	/// the syntax tree given as input is malformed
+	InvalidAst(String),
+}
+
+impl Error {
+	/// Span of the token the error is about, for the errors tied to one.
+	pub fn span(&self) -> Option<Span> {
+		match self {
+			Self::Lex { at }
+			| Self::UnterminatedComment { at }
+			| Self::Parse { at } => Some(*at),
+			_ => None,
+		}
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Lex { .. } => write!(f, "failed to tokenize the code"),
+			Self::UnterminatedComment { .. } => {
+				write!(f, "comment is never closed")
+			},
+			Self::Parse { .. } => write!(f, "failed to parse the code"),
+			Self::UnmatchedJumpRight => write!(f, "`~` without matching `-`"),
+			Self::UnmatchedJumpLeft => write!(f, "`-` without matching `~`"),
+			Self::PointerUnderflow => {
+				write!(
+					f,
+					"pointer moved left of the first cell"
+				)
+			},
+			Self::Io(kind) => write!(f, "I/O error: {kind}"),
+			Self::Diverged { .. } => {
+				write!(
+					f,
+					"formatted code diverges from the input"
+				)
+			},
+			Self::NotIdempotent { .. } => {
+				write!(
+					f,
+					"formatting the code twice gives a different result"
+				)
+			},
+			Self::Protocol(message) => write!(f, "protocol error: {message}"),
+			Self::Config(message) => {
+				write!(f, "invalid configuration: {message}")
+			},
+			Self::InvalidAst(message) => {
+				write!(f, "invalid syntax tree: {message}")
+			},
+		}
+	}
+}
+
+impl From<io::Error> for Error {
+	fn from(err: io::Error) -> Self {
+		Self::Io(err.kind())
+	}
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,69 @@
+use super::*;
+use crate::{parse_and_format_code, run_code};
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+fn run(code: &str) -> String {
+	let mut output = vec![];
+
+	run_code(code, &b""[..], &mut output).unwrap();
+
+	String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn empty_text_must_generate_empty_program() {
+	assert_eq!(generate("").to_string(), "")
+}
+
+#[test]
+fn small_deltas_must_be_added_one_by_one() {
+	assert_eq!(
+		generate("\u{3}\u{1}").to_string(),
+		indoc! {"
+            ? 냥냥냥.
+            냐냐.
+        "}
+	)
+}
+
+#[test]
+fn large_deltas_must_use_multiplication_loops() {
+	assert_eq!(
+		generate("A").to_string(),
+		indoc! {"
+            냥냥냥냥냥냥냥냥~? 냥냥냥냥냥냥냥냥! 냐-? 냥.
+        "}
+	)
+}
+
+#[test]
+fn generated_program_must_print_the_text() {
+	for text in ["Hello World!\n", "냥랭 만세!", "~-?!\"\u{0}\u{7f}"] {
+		assert_eq!(run(&generate(text).to_string()), text)
+	}
+}
+
+#[test]
+fn generated_program_must_already_be_formatted() {
+	let code = generate("Hello World!\n").to_string();
+
+	assert_eq!(parse_and_format_code(&code), Ok(code))
+}
+
+#[test]
+fn generated_program_must_be_shorter_than_naive_increments() {
+	let text = "Hello World!";
+	let naive = text
+		.bytes()
+		.map(|b| b as usize + 1)
+		.sum::<usize>();
+
+	assert!(
+		generate(text)
+			.to_string()
+			.chars()
+			.filter(|c| !c.is_whitespace())
+			.count() < naive / 3
+	)
+}
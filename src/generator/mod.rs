@@ -0,0 +1,89 @@
+use std::iter::repeat_n;
+
+use crate::{
+	lexer::{
+		Token::{self, *},
+		TokenStream,
+	},
+	parser::{ast::Root, parse_ast},
+};
+
+// moving to the counter cell, the loop itself and moving back
+const LOOP_OVERHEAD: usize = 6;
+
+/// Emits tokens adding `delta` to the output cell.
+///
+/// The cell to the left of the output cell is used as the loop counter
+/// of a multiplication loop whenever that is shorter than adding one by
+/// one. Either way, the pointer ends up on the output cell.
+fn add(delta: i8, on_counter: bool, tokens: &mut Vec<Token>) {
+	let m = delta.unsigned_abs() as usize;
+	let (step, back) = if delta < 0 { (Dec, Inc) } else { (Inc, Dec) };
+
+	let best = (2..=m / 2)
+		.map(|a| {
+			let b = (m + a / 2) / a;
+			let rest = m as isize - (a * b) as isize;
+
+			(
+				a + b + rest.unsigned_abs() + LOOP_OVERHEAD,
+				a,
+				b,
+				rest,
+			)
+		})
+		.min_by_key(|&(cost, ..)| cost)
+		.filter(|&(cost, ..)| cost < m);
+
+	let rest = match best {
+		Some((_, a, b, rest)) => {
+			if !on_counter {
+				tokens.push(Left);
+			}
+			tokens.extend(repeat_n(Inc, a));
+			tokens.extend([JumpRight, Right]);
+			tokens.extend(repeat_n(step.clone(), b));
+			tokens.extend([Left, Dec, JumpLeft, Right]);
+
+			rest
+		},
+		None => {
+			if on_counter {
+				tokens.push(Right);
+			}
+
+			m as isize
+		},
+	};
+
+	match rest {
+		0.. => tokens.extend(repeat_n(step, rest as usize)),
+		_ => tokens.extend(repeat_n(back, rest.unsigned_abs())),
+	}
+}
+
+/// Builds a program printing the given text as UTF-8 bytes.
+///
+/// Every byte is printed on its own line, computed from the previous one
+/// so that the output cell never has to be cleared.
+pub fn generate(text: &str) -> Root {
+	let mut tokens = vec![];
+	let mut cell = 0u8;
+
+	for byte in text.bytes() {
+		add(
+			byte.wrapping_sub(cell) as i8,
+			tokens.is_empty(),
+			&mut tokens,
+		);
+		tokens.extend([Out, NewLine]);
+		cell = byte;
+	}
+
+	parse_ast(TokenStream::from(&tokens[..]))
+		.expect("generated tokens always form a valid program")
+}
+
+#[cfg(test)]
+#[path = "generator.spec.rs"]
+mod tests;
@@ -0,0 +1,131 @@
+use super::*;
+use crate::{
+	ir::{compile, compile_naive},
+	parse_code,
+};
+use pretty_assertions::assert_eq;
+
+fn run_with(code: &str, input: &[u8]) -> Result<Vec<u8>, Error> {
+	let mut output = vec![];
+
+	run(
+		&compile(&parse_code(code).unwrap())?,
+		input,
+		&mut output,
+	)?;
+
+	Ok(output)
+}
+
+#[test]
+fn run_hello_world() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/hello.nyan"),
+			b""
+		),
+		Ok(b"Hello World!\n".to_vec())
+	)
+}
+
+#[test]
+fn run_cat() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/cat.nyan"),
+			"냥냥!".as_bytes()
+		),
+		Ok("냥냥!".as_bytes().to_vec())
+	)
+}
+
+#[test]
+fn run_reverse() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/reverse.nyan"),
+			b"nyan"
+		),
+		Ok(b"nayn".to_vec())
+	)
+}
+
+#[test]
+fn run_sierpinski() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/sierpinski.nyan"),
+			b""
+		),
+		Ok(include_bytes!("../../samples/sierpinski.out").to_vec())
+	)
+}
+
+#[test]
+fn run_mandelbrot() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/mandelbrot.nyan"),
+			b""
+		),
+		Ok(include_bytes!("../../samples/mandelbrot.out").to_vec())
+	)
+}
+
+#[test]
+fn run_sierpinski_without_optimization() {
+	let mut output = vec![];
+
+	run(
+		&compile_naive(
+			&parse_code(include_str!(
+				"../../samples/sierpinski.nyan"
+			))
+			.unwrap(),
+		)
+		.unwrap(),
+		&b""[..],
+		&mut output,
+	)
+	.unwrap();
+
+	assert_eq!(
+		output,
+		include_bytes!("../../samples/sierpinski.out")
+	)
+}
+
+#[test]
+fn cells_must_wrap_around() {
+	assert_eq!(run_with("냐.", b""), Ok(vec![u8::MAX]))
+}
+
+#[test]
+fn must_fail_when_pointer_moves_left_of_first_cell() {
+	assert_eq!(
+		run_with("?!!", b""),
+		Err(Error::PointerUnderflow)
+	)
+}
+
+#[test]
+fn skipped_multiplication_loop_must_not_touch_other_cells() {
+	assert_eq!(run_with("~냐! 냥?-.", b""), Ok(vec![0]))
+}
+
+#[test]
+fn every_executor_must_agree_on_pointer_underflow() {
+	use crate::{run_code_with, Executor};
+
+	for code in ["!?", "냥~냐!?-", "냥~냐!!냥??-", "냥~냐!냥?-"] {
+		let [naive, ir, vm] = [Executor::Naive, Executor::Ir, Executor::Vm]
+			.map(|executor| run_code_with(code, executor, &b""[..], vec![]));
+
+		assert_eq!((&ir, &vm), (&naive, &naive), "{code}");
+	}
+
+	assert_eq!(
+		run_code_with("!?", Executor::Ir, &b""[..], vec![]),
+		Err(Error::PointerUnderflow)
+	)
+}
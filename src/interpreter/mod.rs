@@ -0,0 +1,53 @@
+mod tape;
+mod tree;
+
+use std::io::{Read, Write};
+
+pub use tape::Tape;
+pub use tree::run as run_tree;
+
+use crate::{ir::Op, Error};
+
+/// Reads a single byte, or zero once the input is exhausted.
+pub fn read_byte(mut input: impl Read) -> Result<u8, Error> {
+	let mut buf = [0];
+
+	Ok(match input.read(&mut buf)? {
+		0 => 0,
+		_ => buf[0],
+	})
+}
+
+/// Executes the instructions on a fresh tape.
+///
+/// Reading past the end of the input stores zero into the current cell.
+pub fn run(
+	ops: &[Op],
+	mut input: impl Read,
+	mut output: impl Write,
+) -> Result<(), Error> {
+	let mut tape = Tape::new();
+	let mut pc = 0;
+
+	while let Some(&op) = ops.get(pc) {
+		match op {
+			Op::Add(n) => tape.add(n),
+			Op::Move(n) => tape.shift(n)?,
+			Op::Set(n) => tape.set(n),
+			Op::MulAdd { offset, factor } => tape.mul_add(offset, factor)?,
+			Op::Out => output.write_all(&[tape.get()])?,
+			Op::In => tape.set(read_byte(&mut input)?),
+			Op::JumpRight(end) if tape.get() == 0 => pc = end,
+			Op::JumpLeft(start) if tape.get() != 0 => pc = start,
+			Op::JumpRight(_) | Op::JumpLeft(_) => {},
+		}
+
+		pc += 1;
+	}
+
+	Ok(output.flush()?)
+}
+
+#[cfg(test)]
+#[path = "interpreter.spec.rs"]
+mod tests;
@@ -0,0 +1,66 @@
+use crate::Error;
+
+/// Byte cells that wrap around on overflow, growing to the right on demand.
+pub struct Tape {
+	cells: Vec<u8>,
+	p: usize,
+}
+
+impl Tape {
+	pub fn new() -> Self {
+		Self {
+			cells: vec![0],
+			p: 0,
+		}
+	}
+
+	fn index(&mut self, offset: isize) -> Result<usize, Error> {
+		let i = self
+			.p
+			.checked_add_signed(offset)
+			.ok_or(Error::PointerUnderflow)?;
+
+		if i >= self.cells.len() {
+			self.cells.resize(i + 1, 0);
+		}
+
+		Ok(i)
+	}
+
+	#[inline]
+	pub fn get(&self) -> u8 {
+		self.cells[self.p]
+	}
+
+	#[inline]
+	pub fn set(&mut self, value: u8) {
+		self.cells[self.p] = value;
+	}
+
+	#[inline]
+	pub fn add(&mut self, n: u8) {
+		self.set(self.get().wrapping_add(n));
+	}
+
+	pub fn shift(&mut self, offset: isize) -> Result<(), Error> {
+		self.p = self.index(offset)?;
+
+		Ok(())
+	}
+
+	/// Adds the current cell times `factor` to the cell at `offset`.
+	///
+	/// Nothing happens when the current cell is zero, just like the loop
+	/// this was folded from would have been skipped.
+	pub fn mul_add(&mut self, offset: isize, factor: u8) -> Result<(), Error> {
+		let n = self.get();
+
+		if n != 0 {
+			let i = self.index(offset)?;
+
+			self.cells[i] = self.cells[i].wrapping_add(n.wrapping_mul(factor));
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,127 @@
+use std::io::{Read, Write};
+
+use super::{read_byte, Tape};
+use crate::{parser::ast::*, Error};
+
+/// Token of the tree, borrowed as it was parsed.
+#[derive(Clone, Copy)]
+enum Tok<'a> {
+	Head(&'a HeadTok),
+	Body(&'a BodyTok),
+	Tail(&'a TailTok),
+}
+
+/// Tokens of every word, in the order they are executed.
+fn tokens(root: &Root) -> Vec<Tok<'_>> {
+	let Root(code) = root;
+
+	code.leading_sentences
+		.iter()
+		.chain(
+			code.paragraphs
+				.iter()
+				.flat_map(|Paragraph(_, s)| s),
+		)
+		.flat_map(|Sentence(words)| words)
+		.flat_map(|word| {
+			let head = word.head.iter().flat_map(|Head(v)| v);
+			let body = word.body.iter().flat_map(|Body(v)| v);
+			let tail = word.tail.iter().flat_map(|Tail(v)| v);
+
+			head.map(Tok::Head)
+				.chain(body.map(Tok::Body))
+				.chain(tail.map(Tok::Tail))
+		})
+		.collect()
+}
+
+/// Change of nesting depth of the token.
+fn depth(tok: Tok) -> isize {
+	match tok {
+		Tok::Body(BodyTok::JumpRight) => 1,
+		Tok::Body(BodyTok::JumpLeft) => -1,
+		_ => 0,
+	}
+}
+
+fn check_jumps(tokens: &[Tok]) -> Result<(), Error> {
+	let mut open = 0;
+
+	for &tok in tokens {
+		open += depth(tok);
+
+		if open < 0 {
+			return Err(Error::UnmatchedJumpLeft);
+		}
+	}
+
+	match open {
+		0 => Ok(()),
+		_ => Err(Error::UnmatchedJumpRight),
+	}
+}
+
+/// Index of the jump matching the one at `at`, found by scanning the
+/// tokens towards it every time.
+fn matching(tokens: &[Tok], at: usize) -> usize {
+	let mut open = 0;
+	let mut i = at;
+
+	loop {
+		open += depth(tokens[i]);
+
+		if open == 0 {
+			return i;
+		}
+
+		if open > 0 {
+			i += 1;
+		} else {
+			i -= 1;
+		}
+	}
+}
+
+/// Executes the tree token by token on a fresh tape, without lowering or
+/// optimizing it first.
+///
+/// Reading past the end of the input stores zero into the current cell.
+pub fn run(
+	root: &Root,
+	mut input: impl Read,
+	mut output: impl Write,
+) -> Result<(), Error> {
+	let tokens = tokens(root);
+	check_jumps(&tokens)?;
+
+	let mut tape = Tape::new();
+	let mut pc = 0;
+
+	while let Some(&tok) = tokens.get(pc) {
+		match tok {
+			Tok::Head(HeadTok::Inc) => tape.add(1),
+			Tok::Head(HeadTok::Dec) => tape.add(u8::MAX),
+			// debug symbols have no effect on execution
+			Tok::Head(HeadTok::Debug) => {},
+			Tok::Body(BodyTok::Out) => output.write_all(&[tape.get()])?,
+			Tok::Body(BodyTok::In) => tape.set(read_byte(&mut input)?),
+			Tok::Body(BodyTok::JumpRight) if tape.get() == 0 => {
+				pc = matching(&tokens, pc)
+			},
+			Tok::Body(BodyTok::JumpLeft) if tape.get() != 0 => {
+				pc = matching(&tokens, pc)
+			},
+			Tok::Body(BodyTok::JumpRight | BodyTok::JumpLeft) => {},
+			Tok::Tail(TailTok::Right) => tape.shift(1)?,
+			Tok::Tail(TailTok::Left) => tape.shift(-1)?,
+		}
+
+		pc += 1;
+	}
+
+	Ok(output.flush()?)
+}
+
+#[cfg(test)]
+#[path = "tree.spec.rs"]
+mod tests;
@@ -0,0 +1,67 @@
+use super::*;
+use crate::parse_code;
+use pretty_assertions::assert_eq;
+
+fn run_with(code: &str, input: &[u8]) -> Result<Vec<u8>, Error> {
+	let mut output = vec![];
+
+	run(
+		&parse_code(code).unwrap(),
+		input,
+		&mut output,
+	)?;
+
+	Ok(output)
+}
+
+#[test]
+fn run_hello_world() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/hello.nyan"),
+			b""
+		),
+		Ok(b"Hello World!\n".to_vec())
+	)
+}
+
+#[test]
+fn run_reverse() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/reverse.nyan"),
+			b"nyan"
+		),
+		Ok(b"nayn".to_vec())
+	)
+}
+
+#[test]
+fn run_sierpinski() {
+	assert_eq!(
+		run_with(
+			include_str!("../../samples/sierpinski.nyan"),
+			b""
+		),
+		Ok(include_bytes!("../../samples/sierpinski.out").to_vec())
+	)
+}
+
+#[test]
+fn skipped_loops_must_jump_past_nested_loops() {
+	assert_eq!(
+		run_with("~냥~냐-? 냥-냥.", b""),
+		Ok(vec![1])
+	)
+}
+
+#[test]
+fn unmatched_jumps_must_fail_before_running() {
+	assert_eq!(
+		[run_with(".~", b""), run_with(".-~", b"")],
+		[
+			Err(Error::UnmatchedJumpRight),
+			Err(Error::UnmatchedJumpLeft)
+		]
+	)
+}
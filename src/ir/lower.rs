@@ -0,0 +1,52 @@
+use super::Op;
+use crate::parser::ast::*;
+
+pub fn sentences(root: &Root) -> impl Iterator<Item = &Sentence> {
+	let Root(code) = root;
+
+	code.leading_sentences.iter().chain(
+		code.paragraphs
+			.iter()
+			.flat_map(|Paragraph(_, s)| s),
+	)
+}
+
+fn lower_word(word: &Word, ops: &mut Vec<Op>) {
+	for tok in word.head.iter().flat_map(|Head(v)| v) {
+		match tok {
+			HeadTok::Inc => ops.push(Op::Add(1)),
+			HeadTok::Dec => ops.push(Op::Add(u8::MAX)),
+			// debug symbols have no effect on execution
+			HeadTok::Debug => {},
+		}
+	}
+
+	for tok in word.body.iter().flat_map(|Body(v)| v) {
+		ops.push(match tok {
+			BodyTok::Out => Op::Out,
+			BodyTok::In => Op::In,
+			BodyTok::JumpRight => Op::JumpRight(0),
+			BodyTok::JumpLeft => Op::JumpLeft(0),
+		})
+	}
+
+	for tok in word.tail.iter().flat_map(|Tail(v)| v) {
+		ops.push(match tok {
+			TailTok::Right => Op::Move(1),
+			TailTok::Left => Op::Move(-1),
+		})
+	}
+}
+
+/// Translates every token into its own instruction, one by one.
+///
+/// Jump targets are left as zero until they are linked.
+pub fn lower(root: &Root) -> Vec<Op> {
+	let mut ops = vec![];
+
+	for word in sentences(root).flat_map(|Sentence(v)| v) {
+		lower_word(word, &mut ops);
+	}
+
+	ops
+}
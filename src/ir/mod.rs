@@ -0,0 +1,42 @@
+mod lower;
+mod pass;
+
+use crate::{parser::ast::Root, Error};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Op {
+	/// add to the current cell, wrapping around on overflow
+	Add(u8),
+	/// move the pointer by the given amount of cells
+	Move(isize),
+	/// overwrite the current cell
+	Set(u8),
+	/// add the current cell times `factor` to the cell at `offset`
+	MulAdd {
+		offset: isize,
+		factor: u8,
+	},
+	Out,
+	In,
+	/// index of the matching `JumpLeft`
+	JumpRight(usize),
+	/// index of the matching `JumpRight`
+	JumpLeft(usize),
+}
+
+/// Lowers the program one instruction per token, without optimizing it.
+#[cfg(test)]
+pub fn compile_naive(root: &Root) -> Result<Vec<Op>, Error> {
+	pass::link_jumps(lower::lower(root))
+}
+
+/// Lowers the program and runs every optimization pass over it.
+///
+/// Loops are folded before jumps get linked, so the resulting jump
+/// targets always point into the final instruction list.
+pub fn compile(root: &Root) -> Result<Vec<Op>, Error> {
+	let ops = pass::fold_runs(lower::lower(root));
+	let ops = pass::fold_runs(pass::fold_loops(ops));
+
+	pass::link_jumps(ops)
+}
@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use super::Op;
+use crate::Error;
+
+/// Merges adjacent additions and pointer moves, dropping the ones that
+/// cancel each other out.
+///
+/// A move to the left followed by one to the right is kept apart, as the
+/// pointer may go below the first cell in between.
+pub fn fold_runs(ops: Vec<Op>) -> Vec<Op> {
+	let mut out: Vec<Op> = vec![];
+
+	for op in ops {
+		match (out.last_mut(), op) {
+			(Some(Op::Add(a) | Op::Set(a)), Op::Add(b)) => {
+				*a = a.wrapping_add(b)
+			},
+			(Some(Op::Move(a)), Op::Move(b)) if *a >= 0 || b <= 0 => *a += b,
+			(Some(last @ (Op::Add(_) | Op::Set(_))), Op::Set(_)) => *last = op,
+			_ => out.push(op),
+		}
+
+		if matches!(
+			out.last(),
+			Some(Op::Add(0) | Op::Move(0))
+		) {
+			out.pop();
+		}
+	}
+
+	out
+}
+
+fn fold_loop(body: &[Op]) -> Option<Vec<Op>> {
+	if let [Op::Add(n)] = body {
+		// an odd step reaches zero from any value
+		return (n % 2 == 1).then(|| vec![Op::Set(0)]);
+	}
+
+	let mut offset = 0;
+	let mut lowest = 0;
+	let mut deltas = BTreeMap::new();
+
+	for op in body {
+		match *op {
+			Op::Add(n) => {
+				let delta = deltas.entry(offset).or_insert(0u8);
+				*delta = delta.wrapping_add(n);
+			},
+			Op::Move(n) => {
+				offset += n;
+				lowest = lowest.min(offset);
+			},
+			_ => return None,
+		}
+	}
+
+	if offset != 0 || deltas.remove(&0) != Some(u8::MAX) {
+		return None;
+	}
+
+	// the loop fails if it goes below the first cell, which only a
+	// `MulAdd` at its lowest offset checks
+	if lowest < 0
+		&& deltas
+			.get(&lowest)
+			.is_none_or(|&factor| factor == 0)
+	{
+		return None;
+	}
+
+	Some(
+		deltas
+			.into_iter()
+			.filter(|&(_, factor)| factor != 0)
+			.map(|(offset, factor)| Op::MulAdd { offset, factor })
+			.chain([Op::Set(0)])
+			.collect(),
+	)
+}
+
+/// Replaces loops that only add and move with straight-line code.
+///
+/// Clear loops like `~냐-` become `Set(0)`, and loops that decrement the
+/// current cell once per iteration while adding to other cells, like
+/// `~냐? 냥냥!-`, become a `MulAdd` per touched cell followed by `Set(0)`.
+pub fn fold_loops(ops: Vec<Op>) -> Vec<Op> {
+	let mut out = vec![];
+
+	for op in ops {
+		if !matches!(op, Op::JumpLeft(_)) {
+			out.push(op);
+			continue;
+		}
+
+		let folded = out
+			.iter()
+			.rposition(|op| matches!(op, Op::JumpRight(_)))
+			.and_then(|start| Some((start, fold_loop(&out[start + 1..])?)));
+
+		match folded {
+			Some((start, folded)) => {
+				out.truncate(start);
+				out.extend(folded);
+			},
+			None => out.push(op),
+		}
+	}
+
+	out
+}
+
+/// Points every jump at its matching counterpart.
+pub fn link_jumps(mut ops: Vec<Op>) -> Result<Vec<Op>, Error> {
+	let mut starts = vec![];
+
+	for i in 0..ops.len() {
+		match ops[i] {
+			Op::JumpRight(_) => starts.push(i),
+			Op::JumpLeft(_) => {
+				let start = starts
+					.pop()
+					.ok_or(Error::UnmatchedJumpLeft)?;
+
+				ops[start] = Op::JumpRight(i);
+				ops[i] = Op::JumpLeft(start);
+			},
+			_ => {},
+		}
+	}
+
+	if !starts.is_empty() {
+		return Err(Error::UnmatchedJumpRight);
+	}
+
+	Ok(ops)
+}
+
+#[cfg(test)]
+#[path = "pass.spec.rs"]
+mod tests;
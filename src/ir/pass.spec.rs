@@ -0,0 +1,235 @@
+use super::*;
+use crate::{ir::compile, parse_code};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn fold_runs_must_merge_adjacent_adds_and_moves() {
+	assert_eq!(
+		fold_runs(vec![
+			Op::Add(1),
+			Op::Add(1),
+			Op::Add(1),
+			Op::Move(1),
+			Op::Move(1),
+			Op::Out,
+			Op::Add(u8::MAX),
+		]),
+		[Op::Add(3), Op::Move(2), Op::Out, Op::Add(u8::MAX)]
+	)
+}
+
+#[test]
+fn fold_runs_must_drop_cancelling_pairs() {
+	assert_eq!(
+		fold_runs(vec![
+			Op::Add(1),
+			Op::Add(u8::MAX),
+			Op::Move(1),
+			Op::Move(-1),
+		]),
+		[]
+	)
+}
+
+#[test]
+fn fold_runs_must_merge_adds_into_preceding_set() {
+	assert_eq!(
+		fold_runs(vec![Op::Set(0), Op::Add(3)]),
+		[Op::Set(3)]
+	)
+}
+
+#[test]
+fn fold_runs_must_discard_adds_overwritten_by_set() {
+	assert_eq!(
+		fold_runs(vec![Op::Add(3), Op::Set(0)]),
+		[Op::Set(0)]
+	)
+}
+
+#[test]
+fn fold_loops_must_detect_clear_loops() {
+	assert_eq!(
+		fold_loops(vec![
+			Op::JumpRight(0),
+			Op::Add(u8::MAX),
+			Op::JumpLeft(0),
+		]),
+		[Op::Set(0)]
+	)
+}
+
+#[test]
+fn fold_loops_must_detect_clear_loops_with_odd_steps() {
+	assert_eq!(
+		fold_loops(vec![
+			Op::JumpRight(0),
+			Op::Add(3),
+			Op::JumpLeft(0),
+		]),
+		[Op::Set(0)]
+	)
+}
+
+#[test]
+fn fold_loops_must_keep_loops_with_even_steps() {
+	let ops = vec![Op::JumpRight(0), Op::Add(2), Op::JumpLeft(0)];
+
+	assert_eq!(fold_loops(ops.clone()), ops)
+}
+
+#[test]
+fn fold_loops_must_detect_multiplication_loops() {
+	assert_eq!(
+		fold_loops(vec![
+			Op::JumpRight(0),
+			Op::Add(u8::MAX),
+			Op::Move(1),
+			Op::Add(2),
+			Op::Move(-3),
+			Op::Add(1),
+			Op::Move(2),
+			Op::JumpLeft(0),
+		]),
+		[
+			Op::MulAdd {
+				offset: -2,
+				factor: 1
+			},
+			Op::MulAdd {
+				offset: 1,
+				factor: 2
+			},
+			Op::Set(0),
+		]
+	)
+}
+
+#[test]
+fn fold_loops_must_keep_loops_with_side_effects() {
+	let ops = vec![
+		Op::JumpRight(0),
+		Op::Add(u8::MAX),
+		Op::Move(1),
+		Op::Out,
+		Op::Move(-1),
+		Op::JumpLeft(0),
+	];
+
+	assert_eq!(fold_loops(ops.clone()), ops)
+}
+
+#[test]
+fn fold_loops_must_keep_loops_with_unbalanced_moves() {
+	let ops = vec![
+		Op::JumpRight(0),
+		Op::Add(u8::MAX),
+		Op::Move(1),
+		Op::JumpLeft(0),
+	];
+
+	assert_eq!(fold_loops(ops.clone()), ops)
+}
+
+#[test]
+fn fold_loops_must_fold_inner_loops_only() {
+	assert_eq!(
+		fold_loops(vec![
+			Op::JumpRight(0),
+			Op::Move(1),
+			Op::JumpRight(0),
+			Op::Add(u8::MAX),
+			Op::JumpLeft(0),
+			Op::Move(-1),
+			Op::Add(u8::MAX),
+			Op::JumpLeft(0),
+		]),
+		[
+			Op::JumpRight(0),
+			Op::Move(1),
+			Op::Set(0),
+			Op::Move(-1),
+			Op::Add(u8::MAX),
+			Op::JumpLeft(0),
+		]
+	)
+}
+
+#[test]
+fn link_jumps_must_point_to_matching_jumps() {
+	assert_eq!(
+		link_jumps(vec![
+			Op::JumpRight(0),
+			Op::JumpRight(0),
+			Op::Out,
+			Op::JumpLeft(0),
+			Op::JumpLeft(0),
+		]),
+		Ok(vec![
+			Op::JumpRight(4),
+			Op::JumpRight(3),
+			Op::Out,
+			Op::JumpLeft(1),
+			Op::JumpLeft(0),
+		])
+	)
+}
+
+#[test]
+fn link_jumps_must_fail_on_unclosed_jump_right() {
+	assert_eq!(
+		link_jumps(vec![Op::JumpRight(0), Op::Out]),
+		Err(Error::UnmatchedJumpRight)
+	)
+}
+
+#[test]
+fn link_jumps_must_fail_on_unopened_jump_left() {
+	assert_eq!(
+		link_jumps(vec![Op::JumpLeft(0), Op::JumpRight(0)]),
+		Err(Error::UnmatchedJumpLeft)
+	)
+}
+
+#[test]
+fn compile_must_run_every_pass() {
+	assert_eq!(
+		compile(&parse_code("냥냥냥 ~냐-냥냥 ?? .~,-").unwrap()),
+		Ok(vec![
+			Op::Set(2),
+			Op::Move(2),
+			Op::Out,
+			Op::JumpRight(5),
+			Op::In,
+			Op::JumpLeft(3),
+		])
+	)
+}
+
+#[test]
+fn fold_runs_must_keep_moves_that_may_go_below_the_first_cell() {
+	assert_eq!(
+		fold_runs(vec![
+			Op::Move(-1),
+			Op::Move(-1),
+			Op::Move(1),
+			Op::Move(2)
+		]),
+		[Op::Move(-2), Op::Move(3)]
+	)
+}
+
+#[test]
+fn fold_loops_must_keep_loops_going_below_their_touched_cells() {
+	let ops = vec![
+		Op::JumpRight(0),
+		Op::Add(u8::MAX),
+		Op::Move(-2),
+		Op::Move(1),
+		Op::Add(1),
+		Op::Move(1),
+		Op::JumpLeft(0),
+	];
+
+	assert_eq!(fold_loops(ops.clone()), ops)
+}
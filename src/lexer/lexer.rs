@@ -1,16 +1,17 @@
 use nom::{
 	branch::alt,
-	bytes::complete::take_until,
-	character::complete::{char, line_ending, space0},
-	combinator::{cut, eof, map, value},
+	bytes::complete::tag,
+	character::complete::{anychar, char, line_ending, none_of, space0},
+	combinator::{consumed, cut, eof, map, recognize, value},
 	error::{Error, ParseError},
 	multi::{many0, many1},
-	sequence::{delimited, terminated},
-	Finish, IResult,
+	sequence::{delimited, pair, preceded, terminated},
+	Finish, IResult, Offset,
 };
 use str_macro::str as s;
 
-use super::Token;
+use super::{Span, Token};
+use crate::UnknownChars;
 
 char_token! { lex_right: '?' -> Token::Right }
 char_token! { lex_left: '!' -> Token::Left }
@@ -22,55 +23,226 @@ char_token! { lex_jump_right: '~' -> Token::JumpRight }
 char_token! { lex_jump_left: '-' -> Token::JumpLeft }
 char_token! { lex_debug: '뀨' -> Token::Debug }
 
+/// Reads the text of a comment as written between its quotes, where `\"`
+/// stands for a double quote and `\\` for a backslash. Any other backslash
+/// is kept as it is.
+fn unescape(text: &str) -> String {
+	let mut res = String::new();
+	let mut chars = text.chars().peekable();
+
+	while let Some(ch) = chars.next() {
+		match (ch, chars.peek()) {
+			('\\', Some(&next @ ('"' | '\\'))) => {
+				res.push(next);
+				chars.next();
+			},
+			_ => res.push(ch),
+		}
+	}
+
+	res
+}
+
+/// Writes the text of a comment so that lexing reads it back, escaping
+/// double quotes and the backslashes that would otherwise start an escape
+/// or escape the closing quote.
+pub fn escape(text: &str) -> String {
+	let mut res = String::new();
+	let mut chars = text.chars().peekable();
+
+	while let Some(ch) = chars.next() {
+		match (ch, chars.peek()) {
+			('"', _) => res.push_str(r#"\""#),
+			('\\', None | Some('"' | '\\')) => res.push_str(r"\\"),
+			_ => res.push(ch),
+		}
+	}
+
+	res
+}
+
+/// Takes a comment between double quotes, which may contain escaped
+/// quotes and span several lines, keeping its line breaks as they are.
 fn lex_comment<'a, E>(input: &'a str) -> IResult<&'a str, Token, E>
 where
 	E: ParseError<&'a str>,
 {
 	map(
-		delimited(char('"'), take_until(r#"""#), char('"')),
-		|o: &str| Token::Comment(s!(o)),
+		delimited(
+			char('"'),
+			recognize(many0(alt((
+				tag(r#"\""#),
+				tag(r"\\"),
+				recognize(none_of(r#"""#)),
+			)))),
+			char('"'),
+		),
+		|o: &str| Token::Comment(unescape(o)),
 	)(input)
 }
 
-fn lex_newline<'a, E>(input: &'a str) -> IResult<&str, Token, E>
+fn lex_newline<'a, E>(input: &'a str) -> IResult<&'a str, Token, E>
 where
 	E: ParseError<&'a str>,
 {
-	value(Token::NewLine, many1(line_ending))(input)
+	value(
+		Token::NewLine,
+		many1(preceded(space0, line_ending)),
+	)(input)
 }
 
-fn lex_token<'a, E>(input: &'a str) -> IResult<&'a str, Token, E>
+/// Characters ending a run of unknown characters: those starting a token,
+/// and the whitespace around tokens.
+const NOT_UNKNOWN: &str = "?!냥냐.,~-뀨\" \t\r\n";
+
+/// Takes a run of characters that are not part of any token, up to the
+/// next token or the end of the line, without the trailing spaces.
+fn lex_unknown_chars<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
 where
 	E: ParseError<&'a str>,
 {
-	alt((
-		lex_right,
-		lex_left,
-		lex_inc,
-		lex_dec,
-		lex_out,
-		lex_in,
-		lex_jump_right,
-		lex_jump_left,
-		lex_debug,
-		lex_comment,
-		lex_newline,
+	recognize(pair(
+		none_of(NOT_UNKNOWN),
+		many0(preceded(space0, none_of(NOT_UNKNOWN))),
 	))(input)
 }
 
+/// Lexes a token, handling the characters that are not part of any token
+/// as `unknown` tells, giving `None` for those stripped.
+fn lex_token<'a, E>(
+	unknown: UnknownChars,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Option<Token>, E>
+where
+	E: ParseError<&'a str>,
+{
+	move |input| {
+		let token = alt((
+			lex_right,
+			lex_left,
+			lex_inc,
+			lex_dec,
+			lex_out,
+			lex_in,
+			lex_jump_right,
+			lex_jump_left,
+			lex_debug,
+			lex_comment,
+			lex_newline,
+		));
+
+		match unknown {
+			UnknownChars::Error => map(token, Some)(input),
+			UnknownChars::Strip => alt((
+				map(token, Some),
+				value(None, lex_unknown_chars),
+			))(input),
+			UnknownChars::Comment => alt((
+				map(token, Some),
+				map(lex_unknown_chars, |o: &str| {
+					Some(Token::Comment(s!(o)))
+				}),
+			))(input),
+		}
+	}
+}
+
 fn lex_tokenstream<'a, E>(input: &'a str) -> IResult<&'a str, Vec<Token>, E>
 where
 	E: ParseError<&'a str>,
 {
-	many0(delimited(space0, lex_token, space0))(input)
+	map(
+		many0(delimited(
+			space0,
+			lex_token(UnknownChars::Error),
+			space0,
+		)),
+		|o| o.into_iter().flatten().collect(),
+	)(input)
 }
 
+#[allow(dead_code)]
 pub fn lex_code(input: &str) -> Result<Vec<Token>, Error<&str>> {
 	terminated(lex_tokenstream, cut(eof))(input)
 		.finish()
 		.map(|(_, o)| o)
 }
 
+pub fn lex_code_with_spans(
+	input: &str,
+	unknown: UnknownChars,
+) -> Result<Vec<(Token, Span)>, Error<&str>> {
+	terminated(
+		many0(delimited(
+			space0,
+			consumed(lex_token(unknown)),
+			space0,
+		)),
+		cut(eof),
+	)(input)
+	.finish()
+	.map(|(_, o)| spanned(input, o))
+}
+
+/// Pairs the tokens with their span in `input`, leaving out those stripped
+/// and joining the line breaks they separated, as the line breaks around
+/// them would have been one token without them.
+fn spanned(
+	input: &str,
+	tokens: Vec<(&str, Option<Token>)>,
+) -> Vec<(Token, Span)> {
+	let mut res: Vec<(Token, Span)> = vec![];
+
+	for (s, tok) in tokens {
+		let start = input.offset(s);
+		let span = Span::new(start, start + s.len());
+
+		match (tok, res.last_mut()) {
+			(None, _) => {},
+			(Some(Token::NewLine), Some((Token::NewLine, last))) => {
+				last.end = span.end;
+			},
+			(Some(tok), _) => res.push((tok, span)),
+		}
+	}
+
+	res
+}
+
+/// Takes what is left of the line from a character that starts no token,
+/// stopping before trailing spaces, a line break or a double quote.
+fn lex_unknown<'a, E>(input: &'a str) -> IResult<&'a str, Token, E>
+where
+	E: ParseError<&'a str>,
+{
+	map(
+		recognize(pair(
+			anychar,
+			many0(preceded(space0, none_of(" \t\r\n\""))),
+		)),
+		|o: &str| Token::Unknown(s!(o)),
+	)(input)
+}
+
+/// Splits the code into tokens like [`lex_code_with_spans`], but never
+/// fails, giving a [`Token::Unknown`] for every run of text that starts
+/// no token instead.
+pub fn lex_code_recovering(
+	input: &str,
+	unknown: UnknownChars,
+) -> Vec<(Token, Span)> {
+	let (_, o) = many0::<_, _, Error<_>, _>(delimited(
+		space0,
+		consumed(alt((
+			lex_token(unknown),
+			map(lex_unknown, Some),
+		))),
+		space0,
+	))(input)
+	.expect("any character starts a token or unknown text");
+
+	spanned(input, o)
+}
+
 #[cfg(test)]
 #[path = "lexer.spec.rs"]
 mod lexer_tests;
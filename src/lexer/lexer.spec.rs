@@ -51,3 +51,187 @@ fn parse_string2() {
 		Err(Error::new("%$#?", ErrorKind::Eof))
 	)
 }
+
+#[test]
+fn lex_with_spans() {
+	let code = "냥 \"주석\"\n\n ?";
+
+	assert_eq!(
+		lex_code_with_spans(code, UnknownChars::Error),
+		Ok(vec![
+			(Inc, Span::new(0, 3)),
+			(Comment(s!("주석")), Span::new(4, 12)),
+			(NewLine, Span::new(12, 14)),
+			(Right, Span::new(15, 16)),
+		])
+	)
+}
+
+#[test]
+fn unknown_chars_must_be_stripped() {
+	assert_eq!(
+		lex_code_with_spans(
+			"냥 hi there 냥🐱\n",
+			UnknownChars::Strip
+		),
+		Ok(vec![
+			(Inc, Span::new(0, 3)),
+			(Inc, Span::new(13, 16)),
+			(NewLine, Span::new(20, 21)),
+		])
+	)
+}
+
+#[test]
+fn line_breaks_around_stripped_lines_must_be_joined() {
+	assert_eq!(
+		lex_code_with_spans("냥\n%\n\n냐", UnknownChars::Strip),
+		Ok(vec![
+			(Inc, Span::new(0, 3)),
+			(NewLine, Span::new(3, 7)),
+			(Dec, Span::new(7, 10)),
+		])
+	)
+}
+
+#[test]
+fn unknown_chars_must_be_kept_as_comments() {
+	assert_eq!(
+		lex_code_with_spans(
+			"냥 hi there 냥🐱\n",
+			UnknownChars::Comment
+		),
+		Ok(vec![
+			(Inc, Span::new(0, 3)),
+			(
+				Comment(s!("hi there")),
+				Span::new(4, 12)
+			),
+			(Inc, Span::new(13, 16)),
+			(Comment(s!("🐱")), Span::new(16, 20)),
+			(NewLine, Span::new(20, 21)),
+		])
+	)
+}
+
+#[test]
+fn unterminated_comment_must_not_be_stripped() {
+	assert_eq!(
+		lex_code_with_spans("냥 \"주석", UnknownChars::Strip)
+			.map_err(|err| err.input),
+		Err("\"주석")
+	)
+}
+
+#[test]
+fn unknown_text_must_run_to_the_line_end_or_a_comment() {
+	let code = "냥 @@ 냥  \n%\"c\"\n\"주석";
+
+	assert_eq!(
+		lex_code_recovering(code, UnknownChars::Error),
+		vec![
+			(Inc, Span::new(0, 3)),
+			(Unknown(s!("@@ 냥")), Span::new(4, 10)),
+			(NewLine, Span::new(12, 13)),
+			(Unknown(s!("%")), Span::new(13, 14)),
+			(Comment(s!("c")), Span::new(14, 17)),
+			(NewLine, Span::new(17, 18)),
+			(Unknown(s!("\"주석")), Span::new(18, 25)),
+		]
+	)
+}
+
+#[test]
+fn escaped_quotes_and_backslashes_must_be_read_in_comments() {
+	assert_eq!(
+		lex_code(r#""a \"b\" \\" "c\d" "\\\"""#),
+		Ok(vec![
+			Comment(s!(r#"a "b" \"#)),
+			Comment(s!(r"c\d")),
+			Comment(s!(r#"\""#)),
+		])
+	)
+}
+
+#[test]
+fn escaped_comments_must_read_back_as_written() {
+	for text in [r#"a"b"#, r"a\b", r"a\", r#"\""#, r"\\", ""] {
+		assert_eq!(
+			lex_code(&format!("\"{}\"", escape(text))),
+			Ok(vec![Comment(s!(text))])
+		)
+	}
+}
+
+#[test]
+fn multi_line_comments_must_keep_their_line_breaks() {
+	let code = "냥 \"첫 줄\r\n  둘째 줄\n\" ?";
+
+	assert_eq!(
+		lex_code_with_spans(code, UnknownChars::Error),
+		Ok(vec![
+			(Inc, Span::new(0, 3)),
+			(
+				Comment(s!("첫 줄\r\n  둘째 줄\n")),
+				Span::new(4, 28)
+			),
+			(Right, Span::new(29, 30)),
+		])
+	)
+}
+
+#[test]
+fn unterminated_comment_must_fail_at_its_opening_quote() {
+	assert_eq!(
+		lex_code_with_spans("냥 \"a\\\" ?", UnknownChars::Error)
+			.map_err(|err| err.input),
+		Err("\"a\\\" ?")
+	)
+}
+
+#[test]
+fn span_line_col_must_count_characters() {
+	let code = "냥냥\n 냐?";
+
+	assert_eq!(Span::new(11, 12).line_col(code), (2, 3))
+}
+
+#[test]
+fn blank_lines_with_spaces_must_be_a_single_newline() {
+	let code = "냥 \n  \n\t\n ?";
+
+	assert_eq!(
+		lex_code(code),
+		Ok(vec![Inc, NewLine, Right])
+	)
+}
+
+#[test]
+fn span_of_lines_must_exclude_the_last_line_break() {
+	let code = "냥\n냐냐\n?\n";
+
+	assert_eq!(
+		[
+			Span::lines(code, 1, 1),
+			Span::lines(code, 2, 3),
+			Span::lines(code, 3, 10),
+			Span::lines(code, 9, 10),
+		],
+		[
+			Span::new(0, 3),
+			Span::new(4, 12),
+			Span::new(11, 13),
+			Span::new(13, 13),
+		]
+	)
+}
+
+#[test]
+fn span_of_lines_from_line_zero_must_match_line_one() {
+	let code = "냥\n냐냐\n?\n";
+
+	assert_eq!(
+		Span::lines(code, 0, 2),
+		Span::lines(code, 1, 2)
+	)
+}
@@ -1,7 +1,14 @@
 #[macro_use]
 mod r#macro;
+#[allow(clippy::module_inception)]
 mod lexer;
+mod span;
 mod token;
 mod token_stream;
 
-pub use {lexer::lex_code, token::Token, token_stream::TokenStream};
+pub use {
+	lexer::{escape, lex_code_recovering, lex_code_with_spans},
+	span::Span,
+	token::Token,
+	token_stream::TokenStream,
+};
@@ -0,0 +1,46 @@
+/// Byte range of a token in the source code.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Span {
+	pub start: usize,
+	pub end: usize,
+}
+
+impl Span {
+	pub fn new(start: usize, end: usize) -> Self {
+		Self { start, end }
+	}
+
+	/// Span from the start of the `first` line to the end of the `last`
+	/// line, both one-based and included, not counting the line break.
+	///
+	/// Line 0 is taken as line 1, and lines past the end of the source are
+	/// clamped to it.
+	pub fn lines(source: &str, first: usize, last: usize) -> Self {
+		let first = first.max(1);
+		let start = match first {
+			1 => 0,
+			line => source
+				.match_indices('\n')
+				.nth(line - 2)
+				.map_or(source.len(), |(i, _)| i + 1),
+		};
+		let end = source[start..]
+			.match_indices('\n')
+			.nth(last.saturating_sub(first))
+			.map_or(source.len(), |(i, _)| start + i);
+
+		Self::new(start, end.max(start))
+	}
+
+	/// One-based line and column (in characters) where the span starts.
+	pub fn line_col(&self, source: &str) -> (usize, usize) {
+		let before = &source[..self.start];
+		let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+		(
+			before.matches('\n').count() + 1,
+			before[line_start..].chars().count() + 1,
+		)
+	}
+}
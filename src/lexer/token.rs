@@ -13,6 +13,9 @@ pub enum Token {
 	Debug,
 	Comment(String),
 	NewLine,
+	/// text that is not part of any token, only given when lexing with
+	/// recovery
+	Unknown(String),
 }
 
 impl InputLength for Token {
@@ -16,6 +16,7 @@ pub struct TokenStream<'a> {
 }
 
 impl<'a> TokenStream<'a> {
+	#[allow(dead_code)]
 	pub fn new() -> Self {
 		Self { stream: &[] }
 	}
@@ -40,7 +41,7 @@ impl<'a> Display for TokenStream<'a> {
 
 impl<'a> Compare<&Token> for TokenStream<'a> {
 	fn compare(&self, t: &Token) -> CompareResult {
-		match self.stream.get(0) {
+		match self.stream.first() {
 			Some(v) if v == t => CompareResult::Ok,
 			_ => CompareResult::Error,
 		}
@@ -107,9 +108,9 @@ impl<'a> InputTake for TokenStream<'a> {
 
 	#[inline]
 	fn take_split(&self, count: usize) -> (Self, Self) {
-		match self.stream.split_at(count) {
-			(l, r) => (Self::from(r), Self::from(l)),
-		}
+		let (l, r) = self.stream.split_at(count);
+
+		(Self::from(r), Self::from(l))
 	}
 }
 
@@ -1,19 +1,373 @@
+#[cfg(test)]
 #[macro_use]
 mod util;
+mod bytecode;
+mod config;
+mod error;
+mod generator;
+mod interpreter;
+mod ir;
 mod lexer;
+mod lint;
+#[cfg(feature = "lsp")]
+mod lsp;
 mod parser;
+#[cfg(feature = "python")]
+mod python;
+mod translate;
+mod verify;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+use std::{
+	io::{Read, Write},
+	iter::Peekable,
+	str::Chars,
+	vec::IntoIter,
+};
+
+pub use config::{
+	CommentPlacement, CommentWhitespace, Config, FormatConfig, Level, NewLine,
+	UnknownChars,
+};
+pub use error::Error;
+use lexer::lex_code_with_spans;
+pub use lexer::Span;
+pub use lint::{Diagnostic, Fixed, Rule, Severity};
+use nom::InputLength;
+pub use parser::{apply_edits, ast, builder, visit, TextEdit};
+use parser::{ast::Root, format_with_source, parse_ast};
+
+/// Error for code that fails to tokenize from `at`, pointing at the first
+/// character after the spaces there, which is the opening quote of a
+/// comment never closed if it is a `"`.
+fn lex_error(at: usize, rest: &str) -> Error {
+	let trimmed = rest.trim_start_matches([' ', '\t']);
+	let at = at + rest.len() - trimmed.len();
+
+	match trimmed.chars().next() {
+		Some('"') => Error::UnterminatedComment {
+			at: Span::new(at, at + 1),
+		},
+		ch => Error::Lex {
+			at: Span::new(at, at + ch.map_or(0, char::len_utf8)),
+		},
+	}
+}
 
-use std::{iter::Peekable, str::Chars, vec::IntoIter};
+/// Splits the code into tokens along with their spans, reporting the first
+/// character that is not part of a token on failure.
+fn tokenize(
+	code: &str,
+	unknown: UnknownChars,
+) -> Result<Vec<(lexer::Token, Span)>, Error> {
+	lex_code_with_spans(code, unknown)
+		.map_err(|err| lex_error(code.len() - err.input.len(), err.input))
+}
 
-use lexer::lex_code;
-use parser::parse_ast;
+/// Parses the code into its syntax tree, which formats back into code
+/// through its `Display` implementation.
+pub fn parse_code(code: &str) -> Result<Root, Error> {
+	Ok(parse_code_with_spans(code, UnknownChars::Error)?.0)
+}
+
+/// Parses the code, also returning the span of every token that is not a
+/// `NewLine`, in source order.
+fn parse_code_with_spans(
+	code: &str,
+	unknown: UnknownChars,
+) -> Result<(Root, Vec<Span>), Error> {
+	let (tokens, spans): (Vec<_>, Vec<_>) = tokenize(code, unknown)?
+		.into_iter()
+		.unzip();
+	let root =
+		parse_ast(lexer::TokenStream::from(&tokens[..])).map_err(|err| {
+			Error::Parse {
+				at: spans
+					.get(tokens.len() - err.input.input_len())
+					.copied()
+					.unwrap_or(Span::new(code.len(), code.len())),
+			}
+		})?;
+
+	Ok((
+		root,
+		tokens
+			.iter()
+			.zip(spans)
+			.filter(|(tok, _)| **tok != lexer::Token::NewLine)
+			.map(|(_, span)| span)
+			.collect(),
+	))
+}
 
+/// Parses the code like [`parse_code`], but skips what it cannot parse up
+/// to the next line break or comment instead of failing, keeping the
+/// skipped code in the `invalid` node of the word before it on its line.
+pub fn parse_code_recovering(code: &str) -> Root {
+	parse_code_recovering_with_spans(code, UnknownChars::Error).0
+}
+
+/// Parses the code like [`parse_code_with_spans`], handling unknown
+/// characters as the `[format]` settings tell.
+fn parse_with_config(
+	code: &str,
+	config: &FormatConfig,
+) -> Result<(Root, Vec<Span>), Error> {
+	parse_code_with_spans(code, config.unknown_chars)
+}
+
+/// Parses the code like [`parse_code_recovering`], also returning the span
+/// of every token left in the tree, like [`parse_code_with_spans`].
+fn parse_code_recovering_with_spans(
+	code: &str,
+	unknown: UnknownChars,
+) -> (Root, Vec<Span>) {
+	let (tokens, spans): (Vec<_>, Vec<_>) =
+		lexer::lex_code_recovering(code, unknown)
+			.into_iter()
+			.unzip();
+	let (root, skipped) = parser::parse_ast_recovering(
+		lexer::TokenStream::from(&tokens[..]),
+		|range| {
+			let first = spans[range.start];
+			let error = match &tokens[range.start] {
+				lexer::Token::Unknown(text) => lex_error(first.start, text),
+				_ => Error::Parse { at: first },
+			};
+			let span = Span::new(first.start, spans[range.end - 1].end);
+
+			ast::Invalid {
+				span,
+				text: code[span.start..span.end].to_string(),
+				error,
+			}
+		},
+	);
+	let mut kept = vec![true; tokens.len()];
+	for range in skipped {
+		kept[range].fill(false);
+	}
+
+	(
+		root,
+		tokens
+			.iter()
+			.zip(spans)
+			.zip(kept)
+			.filter(|((tok, _), kept)| *kept && **tok != lexer::Token::NewLine)
+			.map(|((_, span), _)| span)
+			.collect(),
+	)
+}
+
+/// Formats the code, keeping the regions marked by the `"nyanfmt: off"`,
+/// `"nyanfmt: on"` and `"nyanfmt: skip"` comments as they are. Use
+/// [`format_code`] to know why the code can't be formatted.
+///
+/// Unlike earlier versions, which formatted the regions like the rest of
+/// the code, it follows the default configuration of [`format_code`].
+#[allow(clippy::result_unit_err)]
 pub fn parse_and_format_code(code: &str) -> Result<String, ()> {
-	Ok(parse_ast(lexer::TokenStream::from(
-		&lex_code(code).map_err(|_| ())?[..],
+	format_code(code, &Config::default()).map_err(|_| ())
+}
+
+/// Formats the code like [`parse_and_format_code`], following the
+/// `[format]` settings of the configuration.
+pub fn format_code(code: &str, config: &Config) -> Result<String, Error> {
+	let (root, spans) = parse_with_config(code, &config.format)?;
+	Ok(format_with_source(&root, &spans, code, &config.format))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AstFormat {
+	/// the `Debug` representation of the tree
+	#[default]
+	Debug,
+	/// the tree serialized to JSON
+	#[cfg(feature = "serde")]
+	Json,
+	/// S-expressions with the byte span each node covers
+	Sexp,
+}
+
+/// Prints the tree the code is parsed into.
+pub fn dump_ast(code: &str, format: AstFormat) -> Result<String, Error> {
+	let (root, spans) = parse_code_with_spans(code, UnknownChars::Error)?;
+
+	Ok(match format {
+		AstFormat::Debug => format!("{root:#?}\n"),
+		#[cfg(feature = "serde")]
+		AstFormat::Json => serde_json::to_string_pretty(&root).unwrap() + "\n",
+		AstFormat::Sexp => parser::to_sexp(&root, &spans),
+	})
+}
+
+/// Formats a tree given as JSON, in the schema of `docs/ast.schema.json`
+/// that [`dump_ast`] also prints.
+#[cfg(feature = "serde")]
+pub fn format_ast_json(json: &str) -> Result<String, Error> {
+	let root: Root = serde_json::from_str(json)
+		.map_err(|err| Error::InvalidAst(err.to_string()))?;
+	parser::validate(&root)?;
+
+	Ok(root.to_string())
+}
+
+/// Formats only the sentences and comments overlapping `range`, returning
+/// the edits to apply to the code.
+pub fn format_code_range(
+	code: &str,
+	range: Span,
+	config: &Config,
+) -> Result<Vec<TextEdit>, Error> {
+	let (root, spans) = parse_with_config(code, &config.format)?;
+
+	Ok(parser::format_range(
+		&root,
+		&spans,
+		code,
+		range,
+		&config.format,
 	))
-	.map_err(|_| ())?
-	.to_string())
+}
+
+/// Checks that `output` has exactly the same instructions and comments
+/// as `input`, in the same order, reading the characters that are not
+/// part of any token and comparing comments as the configuration tells.
+pub fn verify_formatting(
+	input: &str,
+	output: &str,
+	config: &Config,
+) -> Result<(), Error> {
+	verify::verify(input, output, &config.format)
+}
+
+/// Checks that formatting `formatted` once more with the same
+/// configuration leaves it unchanged.
+pub fn check_idempotency(
+	formatted: &str,
+	config: &Config,
+) -> Result<(), Error> {
+	verify::check_idempotency(formatted, config)
+}
+
+/// Checks that formatting `formatted` once more with [`format()`] leaves it
+/// unchanged.
+pub fn check_legacy_idempotency(formatted: &str) -> Result<(), Error> {
+	verify::check_legacy_idempotency(formatted)
+}
+
+/// Reports suspicious constructs in the code, failing only if it cannot be
+/// parsed.
+pub fn lint_code(
+	code: &str,
+	config: &Config,
+) -> Result<Vec<Diagnostic>, Error> {
+	let (root, spans) = parse_with_config(code, &config.format)?;
+
+	Ok(lint::lint(&root, &spans, config))
+}
+
+/// Fixes the lint findings that can be fixed without changing what the
+/// program does, formatting the result. Fails only if the code cannot be
+/// parsed.
+pub fn fix_code(code: &str, config: &Config) -> Result<Fixed, Error> {
+	let (root, spans) = parse_with_config(code, &config.format)?;
+
+	Ok(lint::fix(&root, &spans, code, config))
+}
+
+/// Runs the language server over stdin and stdout, until the client shuts
+/// it down.
+#[cfg(feature = "lsp")]
+pub fn run_language_server() -> Result<(), Error> {
+	lsp::run()
+}
+
+/// Generates code printing `text` when run. Any text can be printed, so
+/// this never fails.
+pub fn generate_code(text: &str) -> String {
+	generator::generate(text).to_string()
+}
+
+/// Translates the code into a WebAssembly text module, failing if it
+/// cannot be parsed or its jumps are unmatched.
+pub fn translate_to_wat(code: &str) -> Result<String, Error> {
+	Ok(translate::to_wat(&ir::compile(
+		&parse_code(code)?,
+	)?))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Executor {
+	/// walk the syntax tree token by token, without any optimization
+	Naive,
+	/// interpret the optimized IR
+	#[default]
+	Ir,
+	/// compile the optimized IR into bytecode and run it on the VM
+	Vm,
+}
+
+/// Code prepared for an executor, which can be run any number of times.
+pub struct Program(Compiled);
+
+enum Compiled {
+	Tree(Root),
+	Ops(Vec<ir::Op>),
+	Bytecode(Vec<u8>),
+}
+
+impl Program {
+	/// Runs the program on a fresh tape, failing if the pointer moves left
+	/// of the first cell or reading the input or writing the output fails.
+	pub fn run(
+		&self,
+		input: impl Read,
+		output: impl Write,
+	) -> Result<(), Error> {
+		match &self.0 {
+			Compiled::Tree(root) => interpreter::run_tree(root, input, output),
+			Compiled::Ops(ops) => interpreter::run(ops, input, output),
+			Compiled::Bytecode(code) => bytecode::run(code, input, output),
+		}
+	}
+}
+
+/// Parses the code and prepares it for the executor, leaving only the
+/// execution to [`Program::run`].
+pub fn compile_code(code: &str, executor: Executor) -> Result<Program, Error> {
+	let root = parse_code(code)?;
+
+	Ok(Program(match executor {
+		Executor::Naive => Compiled::Tree(root),
+		Executor::Ir => Compiled::Ops(ir::compile(&root)?),
+		Executor::Vm => {
+			Compiled::Bytecode(bytecode::assemble(&ir::compile(&root)?))
+		},
+	}))
+}
+
+/// Runs the code with the given executor. Fails if the code cannot be
+/// parsed, its jumps are unmatched, or [`Program::run`] fails.
+pub fn run_code_with(
+	code: &str,
+	executor: Executor,
+	input: impl Read,
+	output: impl Write,
+) -> Result<(), Error> {
+	compile_code(code, executor)?.run(input, output)
+}
+
+/// Runs the code with the default executor, failing like
+/// [`run_code_with`].
+pub fn run_code(
+	code: &str,
+	input: impl Read,
+	output: impl Write,
+) -> Result<(), Error> {
+	run_code_with(code, Executor::default(), input, output)
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -68,7 +422,7 @@ impl<'a> From<Lexer<'a>> for Vec<Token> {
 		let mut v: Vec<Token> = vec![];
 		let mut is_span = false;
 
-		while let Some(ch) = lexer.code.next() {
+		for ch in lexer.code.by_ref() {
 			let Some(token) = Lexer::tokenize(ch) else {
                 continue;
             };
@@ -0,0 +1,270 @@
+use std::mem;
+
+use super::{
+	check, items,
+	rules::{pointer_positions, Finding},
+	Diagnostic, Item, Rule,
+};
+use crate::{
+	lexer::Span,
+	parser::{ast::*, format_with_source, verbatim_items},
+	Config,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fixed {
+	/// the fixed program, formatted
+	pub code: String,
+	/// the findings that were fixed, with their spans in the original code
+	pub applied: Vec<Diagnostic>,
+	/// the fixable findings that were kept since fixing them would change
+	/// what the program does
+	pub refused: Vec<Diagnostic>,
+}
+
+/// Why fixing the finding would change what the program does, if it
+/// would.
+fn refusal(
+	finding: &Finding,
+	items: &[Item],
+	positions: &[(Option<isize>, usize)],
+) -> Option<&'static str> {
+	let start = finding.range.start;
+
+	match (finding.rule, items[start]) {
+		(Rule::CancelPair, Item::Tail(TailTok::Left)) => {
+			match positions[start] {
+				(Some(1..), _) => None,
+				_ => Some(
+					"`!?` is kept since the pointer might be on the first cell, where `!` fails",
+				),
+			}
+		},
+		_ => None,
+	}
+}
+
+/// What is left of a sentence once instructions are removed from it.
+enum Kept {
+	/// the comments of words removed before any kept word, which now come
+	/// before the sentence on their own lines
+	Comment(Comment),
+	Sentence(Sentence),
+}
+
+fn retain_sentence(
+	Sentence(words): Sentence,
+	keep: &mut impl FnMut() -> bool,
+) -> Vec<Kept> {
+	let mut kept = vec![];
+	let mut kept_words: Vec<Word> = vec![];
+
+	for word in words {
+		let word = Word {
+			head: word
+				.head
+				.map(|Head(v)| {
+					Head(
+						v.into_iter()
+							.filter(|_| keep())
+							.collect(),
+					)
+				})
+				.filter(|Head(v)| !v.is_empty()),
+			body: word
+				.body
+				.map(|Body(v)| {
+					Body(
+						v.into_iter()
+							.filter(|_| keep())
+							.collect(),
+					)
+				})
+				.filter(|Body(v)| !v.is_empty()),
+			tail: word
+				.tail
+				.map(|Tail(v)| {
+					Tail(
+						v.into_iter()
+							.filter(|_| keep())
+							.collect(),
+					)
+				})
+				.filter(|Tail(v)| !v.is_empty()),
+			comments: word
+				.comments
+				.into_iter()
+				.inspect(|_| {
+					keep();
+				})
+				.collect(),
+			invalid: word.invalid,
+		};
+
+		if word.head.is_some() || word.body.is_some() || word.tail.is_some() {
+			kept_words.push(word);
+		} else if let Some(previous) = kept_words.last_mut() {
+			previous.comments.extend(word.comments);
+		} else {
+			kept.extend(
+				word.comments
+					.into_iter()
+					.map(Kept::Comment),
+			);
+		}
+	}
+	if !kept_words.is_empty() {
+		kept.push(Kept::Sentence(Sentence(kept_words)));
+	}
+
+	kept
+}
+
+/// Removes the instructions for which `removed` is `true`, in the order of
+/// [`items`]. Comments are always kept.
+///
+/// The comments of a paragraph left without sentences move to the next
+/// paragraph, or to the end of the code. Those of a removed word stay on
+/// the line of the word before it, or go on their own lines above the
+/// sentence when no word is left before it.
+fn remove(Root(code): Root, removed: &[bool]) -> Root {
+	let mut removed = removed.iter();
+	let mut keep = || !removed.next().unwrap();
+
+	let mut leading_sentences = vec![];
+	let mut paragraphs: Vec<Paragraph> = vec![];
+	let mut pending = vec![];
+	let mut add = |kept: Kept, pending: &mut Vec<Comment>| match kept {
+		Kept::Comment(comment) => pending.push(comment),
+		Kept::Sentence(sentence) => {
+			if !pending.is_empty() {
+				paragraphs.push(Paragraph(
+					mem::take(pending),
+					vec![sentence],
+				));
+			} else if let Some(Paragraph(_, sentences)) = paragraphs.last_mut()
+			{
+				sentences.push(sentence);
+			} else {
+				leading_sentences.push(sentence);
+			}
+		},
+	};
+
+	for sentence in code.leading_sentences {
+		for kept in retain_sentence(sentence, &mut keep) {
+			add(kept, &mut pending);
+		}
+	}
+	for Paragraph(comments, sentences) in code.paragraphs {
+		comments.iter().for_each(|_| {
+			keep();
+		});
+		pending.extend(comments);
+
+		for sentence in sentences {
+			for kept in retain_sentence(sentence, &mut keep) {
+				add(kept, &mut pending);
+			}
+		}
+	}
+	pending.extend(code.trailing_comments);
+
+	Root(Code {
+		leading_sentences,
+		paragraphs,
+		trailing_comments: pending,
+	})
+}
+
+/// Applies every fix that does not change what the program does, until
+/// none is left.
+///
+/// Removing code can bring new findings together, such as the `냥냐` left
+/// once the `냥냐` inside `냥냥냐냐` is removed, so the rules run again on
+/// the fixed program each time.
+///
+/// Regions kept as they are by directive comments are left untouched.
+pub fn fix(
+	root: &Root,
+	spans: &[Span],
+	source: &str,
+	config: &Config,
+) -> Fixed {
+	let mut root = root.clone();
+	let mut origin = (0..spans.len()).collect::<Vec<_>>();
+	let mut applied = vec![];
+
+	loop {
+		let items = items(&root);
+		let positions = pointer_positions(&items);
+		let spans = origin
+			.iter()
+			.map(|&i| spans[i])
+			.collect::<Vec<_>>();
+		let fixable = Rule::ALL
+			.into_iter()
+			.filter(Rule::is_fixable);
+		let verbatim = verbatim_items(&root);
+		let (safe, refused): (Vec<_>, Vec<_>) =
+			check(&root, &items, config, fixable)
+				.into_iter()
+				.filter(|(finding, _)| {
+					!verbatim.iter().any(|items| {
+						items.start < finding.range.end
+							&& finding.range.start < items.end
+					})
+				})
+				.partition(|(finding, _)| {
+					refusal(finding, &items, &positions).is_none()
+				});
+
+		if safe.is_empty() {
+			let mut refused = refused
+				.into_iter()
+				.map(|(finding, severity)| {
+					let reason = refusal(&finding, &items, &positions).unwrap();
+					Diagnostic::new(
+						Finding {
+							message: reason.to_string(),
+							..finding
+						},
+						severity,
+						&spans,
+					)
+				})
+				.collect::<Vec<_>>();
+
+			applied.sort_by_key(|d: &Diagnostic| (d.span.start, d.span.end));
+			refused.sort_by_key(|d| (d.span.start, d.span.end));
+
+			return Fixed {
+				code: format_with_source(&root, &spans, source, &config.format),
+				applied,
+				refused,
+			};
+		}
+
+		let mut removed = vec![false; items.len()];
+		for (finding, severity) in safe {
+			for i in finding.range.clone() {
+				removed[i] |= !matches!(items[i], Item::Comment(_));
+			}
+			applied.push(Diagnostic::new(
+				finding, severity, &spans,
+			));
+		}
+
+		root = remove(root, &removed);
+		origin = origin
+			.into_iter()
+			.zip(&removed)
+			.filter(|(_, &removed)| !removed)
+			.map(|(i, _)| i)
+			.collect();
+	}
+}
+
+#[cfg(test)]
+#[path = "fix.spec.rs"]
+mod tests;
@@ -0,0 +1,128 @@
+use crate::{fix_code, Config, Diagnostic, Rule};
+use pretty_assertions::assert_eq;
+
+type Snippets<'a> = Vec<(Rule, &'a str)>;
+
+fn fix(code: &str) -> (String, Snippets<'_>, Snippets<'_>) {
+	let fixed = fix_code(code, &Config::default()).unwrap();
+	let snippets = |diagnostics: Vec<Diagnostic>| {
+		diagnostics
+			.into_iter()
+			.map(|d| (d.rule, &code[d.span.start..d.span.end]))
+			.collect()
+	};
+
+	(
+		fixed.code,
+		snippets(fixed.applied),
+		snippets(fixed.refused),
+	)
+}
+
+#[test]
+fn code_without_findings_must_only_be_formatted() {
+	assert_eq!(
+		fix("냥냥   ?."),
+		(
+			String::from("냥냥? .\n"),
+			vec![],
+			vec![]
+		)
+	)
+}
+
+#[test]
+fn cancelling_pairs_must_be_removed() {
+	assert_eq!(
+		fix("냥냥냐. ?!."),
+		(
+			String::from("냥. .\n"),
+			vec![(Rule::CancelPair, "냥냐"), (Rule::CancelPair, "?!"),],
+			vec![]
+		)
+	)
+}
+
+#[test]
+fn nested_cancelling_pairs_must_be_removed() {
+	assert_eq!(
+		fix("냥냥냐냐."),
+		(
+			String::from(".\n"),
+			vec![(Rule::CancelPair, "냥냥냐냐"), (Rule::CancelPair, "냥냐"),],
+			vec![]
+		)
+	)
+}
+
+#[test]
+fn debug_symbols_must_be_removed() {
+	assert_eq!(
+		fix("뀨\n냥뀨."),
+		(
+			String::from("냥.\n"),
+			vec![(Rule::DebugSymbol, "뀨"), (Rule::DebugSymbol, "뀨"),],
+			vec![]
+		)
+	)
+}
+
+#[test]
+fn dead_loops_must_be_removed_keeping_comments() {
+	assert_eq!(
+		fix("냥~냐-~\"주석\"?~.-!-."),
+		(
+			String::from("냥~ 냐- \"주석\" .\n"),
+			vec![(Rule::DeadLoop, "~\"주석\"?~.-!-")],
+			vec![]
+		)
+	)
+}
+
+#[test]
+fn cancelling_moves_on_the_first_cell_must_be_refused() {
+	assert_eq!(
+		fix("!?. ?!?!"),
+		(
+			String::from("!? .\n"),
+			vec![(Rule::CancelPair, "?!"), (Rule::CancelPair, "?!")],
+			vec![(Rule::CancelPair, "!?")]
+		)
+	)
+}
+
+#[test]
+fn cancelling_moves_after_unbalanced_loops_must_be_refused() {
+	assert_eq!(
+		fix("?~?-!?"),
+		(
+			String::from("? ~? -!?\n"),
+			vec![],
+			vec![(Rule::CancelPair, "!?")]
+		)
+	)
+}
+
+#[test]
+fn regions_kept_by_directives_must_not_be_fixed() {
+	assert_eq!(
+		fix("\"nyanfmt: skip\"\n냥냐 뀨\n냥냐  뀨"),
+		(
+			String::from("\"nyanfmt: skip\"\n냥냐 뀨\n"),
+			vec![(Rule::CancelPair, "냥냐"), (Rule::DebugSymbol, "뀨"),],
+			vec![]
+		)
+	)
+}
+
+#[test]
+fn comments_of_removed_words_must_be_kept() {
+	assert_eq!(
+		fix("? 냥냐 \"a\" .\n냥냐 \"b\" 냥"),
+		(
+			String::from("? \"a\" .\n\n\"b\"\n냥\n"),
+			vec![(Rule::CancelPair, "냥냐"), (Rule::CancelPair, "냥냐")],
+			vec![]
+		)
+	)
+}
@@ -0,0 +1,108 @@
+use super::*;
+use crate::{fix_code, lint_code};
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+fn lint_with<'a>(
+	code: &'a str,
+	config: &Config,
+) -> Vec<(Rule, Severity, &'a str)> {
+	lint_code(code, config)
+		.unwrap()
+		.into_iter()
+		.map(|d| {
+			(
+				d.rule,
+				d.severity,
+				&code[d.span.start..d.span.end],
+			)
+		})
+		.collect()
+}
+
+#[test]
+fn allow_comments_must_suppress_rules_in_their_paragraph() {
+	let code = indoc! {r#"
+		뀨
+
+		"nyanfmt-allow: debug-symbol, cancel-pair"
+		뀨냥냐
+
+		"주석"
+		뀨
+	"#};
+
+	assert_eq!(
+		lint_with(code, &Config::default()),
+		[
+			(
+				Rule::DebugSymbol,
+				Severity::Warning,
+				"뀨"
+			),
+			(
+				Rule::DebugSymbol,
+				Severity::Warning,
+				"뀨"
+			),
+		]
+	);
+	assert_eq!(
+		lint_code(code, &Config::default())
+			.unwrap()
+			.iter()
+			.map(|d| d.span.line_col(code).0)
+			.collect::<Vec<_>>(),
+		[1, 7]
+	)
+}
+
+#[test]
+fn allow_comments_must_ignore_unknown_rules() {
+	assert_eq!(
+		lint_with(
+			"\"nyanfmt-allow: no-such-rule\"\n뀨",
+			&Config::default()
+		),
+		[(
+			Rule::DebugSymbol,
+			Severity::Warning,
+			"뀨"
+		)]
+	)
+}
+
+#[test]
+fn configured_levels_must_apply() {
+	let config = Config::from_toml(indoc! {r#"
+		[lint]
+		debug-symbol = "off"
+		cancel-pair = "error"
+	"#})
+	.unwrap();
+
+	assert_eq!(
+		lint_with("뀨냥냐", &config),
+		[(
+			Rule::CancelPair,
+			Severity::Error,
+			"냥냐"
+		)]
+	)
+}
+
+#[test]
+fn fixes_must_skip_rules_turned_off_or_allowed() {
+	let config = Config::from_toml("[lint]\ndebug-symbol = \"off\"").unwrap();
+	let fixed = fix_code(
+		"뀨냥냐.\n\n\"nyanfmt-allow: cancel-pair\"\n?!.",
+		&config,
+	)
+	.unwrap();
+
+	assert_eq!(
+		fixed.code,
+		"뀨.\n\n\"nyanfmt-allow: cancel-pair\"\n?! .\n"
+	);
+	assert_eq!(fixed.applied.len(), 1)
+}
@@ -0,0 +1,273 @@
+mod fix;
+mod rules;
+
+use std::{
+	fmt::{self, Display, Formatter},
+	ops::Range,
+};
+
+use serde::Deserialize;
+
+pub use fix::{fix, Fixed};
+#[cfg(feature = "lsp")]
+pub use rules::matching_jumps;
+
+use crate::{
+	lexer::Span,
+	parser::{ast::*, visit::Visitor},
+	Config,
+};
+use rules::Finding;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+	Warning,
+	Error,
+}
+
+impl Display for Severity {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Warning => "warning",
+				Self::Error => "error",
+			}
+		)
+	}
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+	/// a `~` or `-` without its counterpart
+	UnmatchedJump,
+	/// `냥냐`, `냐냥`, `?!` or `!?` undoing each other
+	CancelPair,
+	/// `~-`, which never ends once entered
+	EmptyLoop,
+	/// a loop right after another loop, which is never entered
+	DeadLoop,
+	/// a `뀨` left in the code
+	DebugSymbol,
+	/// the pointer certainly moves to the left of the first cell
+	PointerUnderflow,
+}
+
+impl Rule {
+	pub const ALL: [Rule; 6] = [
+		Self::UnmatchedJump,
+		Self::CancelPair,
+		Self::EmptyLoop,
+		Self::DeadLoop,
+		Self::DebugSymbol,
+		Self::PointerUnderflow,
+	];
+
+	pub fn from_id(id: &str) -> Option<Self> {
+		Self::ALL
+			.into_iter()
+			.find(|rule| rule.id() == id)
+	}
+
+	pub fn id(&self) -> &'static str {
+		match self {
+			Self::UnmatchedJump => "unmatched-jump",
+			Self::CancelPair => "cancel-pair",
+			Self::EmptyLoop => "empty-loop",
+			Self::DeadLoop => "dead-loop",
+			Self::DebugSymbol => "debug-symbol",
+			Self::PointerUnderflow => "pointer-underflow",
+		}
+	}
+
+	pub fn severity(&self) -> Severity {
+		match self {
+			Self::UnmatchedJump | Self::PointerUnderflow => Severity::Error,
+			Self::CancelPair
+			| Self::EmptyLoop
+			| Self::DeadLoop
+			| Self::DebugSymbol => Severity::Warning,
+		}
+	}
+
+	/// Whether findings of this rule can be fixed by removing the code
+	/// they cover.
+	pub fn is_fixable(&self) -> bool {
+		matches!(
+			self,
+			Self::CancelPair | Self::DeadLoop | Self::DebugSymbol
+		)
+	}
+
+	fn check(&self, items: &[Item]) -> Vec<Finding> {
+		match self {
+			Self::UnmatchedJump => rules::unmatched_jump(items),
+			Self::CancelPair => rules::cancel_pair(items),
+			Self::EmptyLoop => rules::empty_loop(items),
+			Self::DeadLoop => rules::dead_loop(items),
+			Self::DebugSymbol => rules::debug_symbol(items),
+			Self::PointerUnderflow => rules::pointer_underflow(items),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+	pub rule: Rule,
+	pub severity: Severity,
+	pub span: Span,
+	pub message: String,
+}
+
+impl Diagnostic {
+	fn new(finding: Finding, severity: Severity, spans: &[Span]) -> Self {
+		Self {
+			rule: finding.rule,
+			severity,
+			span: Span::new(
+				spans[finding.range.start].start,
+				spans[finding.range.end - 1].end,
+			),
+			message: finding.message,
+		}
+	}
+}
+
+/// A token of the program, in source order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Item<'a> {
+	Head(&'a HeadTok),
+	Body(&'a BodyTok),
+	Tail(&'a TailTok),
+	Comment(&'a Comment),
+}
+
+/// Collects the tokens of the nodes it visits.
+struct Items<'a>(Vec<Item<'a>>);
+
+impl<'a> Visitor<'a> for Items<'a> {
+	fn visit_head_tok(&mut self, tok: &'a HeadTok) {
+		self.0.push(Item::Head(tok));
+	}
+
+	fn visit_body_tok(&mut self, tok: &'a BodyTok) {
+		self.0.push(Item::Body(tok));
+	}
+
+	fn visit_tail_tok(&mut self, tok: &'a TailTok) {
+		self.0.push(Item::Tail(tok));
+	}
+
+	fn visit_comment(&mut self, comment: &'a Comment) {
+		self.0.push(Item::Comment(comment));
+	}
+}
+
+fn sentence_items(sentence: &Sentence) -> Vec<Item<'_>> {
+	let mut items = Items(vec![]);
+	items.visit_sentence(sentence);
+
+	items.0
+}
+
+/// Flattens the tree back into its tokens, leaving out line breaks.
+///
+/// The `n`th item corresponds to the `n`th token of the source that is
+/// not a `NewLine`.
+pub fn items(root: &Root) -> Vec<Item<'_>> {
+	let mut items = Items(vec![]);
+	items.visit_root(root);
+
+	items.0
+}
+
+/// Prefix of the comments allowing rules in the sentences of their
+/// paragraph, followed by comma separated rule ids.
+const ALLOW: &str = "nyanfmt-allow:";
+
+/// Rules allowed by the comments of every paragraph, along with the
+/// items of its sentences.
+fn allowed(root: &Root) -> Vec<(Range<usize>, Vec<Rule>)> {
+	let Root(code) = root;
+	let mut start = code
+		.leading_sentences
+		.iter()
+		.flat_map(sentence_items)
+		.count();
+
+	code.paragraphs
+		.iter()
+		.map(|Paragraph(comments, sentences)| {
+			let rules = comments
+				.iter()
+				.filter_map(|Comment(c)| c.trim().strip_prefix(ALLOW))
+				.flat_map(|ids| ids.split(','))
+				.filter_map(|id| Rule::from_id(id.trim()))
+				.collect();
+
+			start += comments.len();
+			let end = start
+				+ sentences
+					.iter()
+					.flat_map(sentence_items)
+					.count();
+			let range = start..end;
+			start = end;
+
+			(range, rules)
+		})
+		.collect()
+}
+
+/// Runs the rules turned on in `config`, leaving out the findings allowed
+/// by comments, and gives each finding its configured severity.
+fn check(
+	root: &Root,
+	items: &[Item],
+	config: &Config,
+	rules: impl Iterator<Item = Rule>,
+) -> Vec<(Finding, Severity)> {
+	let allowed = allowed(root);
+
+	rules
+		.filter_map(|rule| Some((rule, config.severity(rule)?)))
+		.flat_map(|(rule, severity)| {
+			rule.check(items)
+				.into_iter()
+				.map(move |finding| (finding, severity))
+		})
+		.filter(|(finding, _)| {
+			!allowed.iter().any(|(range, rules)| {
+				range.contains(&finding.range.start)
+					&& rules.contains(&finding.rule)
+			})
+		})
+		.collect()
+}
+
+/// Runs the rules over the program, as configured.
+///
+/// `spans` holds the span of every token that is not a `NewLine`, as
+/// returned by the lexer. Diagnostics are sorted by their position.
+pub fn lint(root: &Root, spans: &[Span], config: &Config) -> Vec<Diagnostic> {
+	let items = items(root);
+	let mut diagnostics = check(
+		root,
+		&items,
+		config,
+		Rule::ALL.into_iter(),
+	)
+	.into_iter()
+	.map(|(finding, severity)| Diagnostic::new(finding, severity, spans))
+	.collect::<Vec<_>>();
+
+	diagnostics.sort_by_key(|d| (d.span.start, d.span.end));
+
+	diagnostics
+}
+
+#[cfg(test)]
+#[path = "lint.spec.rs"]
+mod tests;
@@ -0,0 +1,262 @@
+use std::ops::Range;
+
+use super::{Item, Rule};
+use crate::parser::ast::*;
+
+/// A finding of a rule, covering `items[range]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+	pub rule: Rule,
+	pub range: Range<usize>,
+	pub message: String,
+}
+
+impl Finding {
+	fn new(
+		rule: Rule,
+		range: Range<usize>,
+		message: impl Into<String>,
+	) -> Self {
+		Self {
+			rule,
+			range,
+			message: message.into(),
+		}
+	}
+}
+
+/// Index of the matching jump for every `~` and `-` that has one.
+pub fn matching_jumps(items: &[Item]) -> Vec<Option<usize>> {
+	let mut matching = vec![None; items.len()];
+	let mut opened = vec![];
+
+	for (i, item) in items.iter().enumerate() {
+		match item {
+			Item::Body(BodyTok::JumpRight) => opened.push(i),
+			Item::Body(BodyTok::JumpLeft) => {
+				if let Some(start) = opened.pop() {
+					matching[start] = Some(i);
+					matching[i] = Some(start);
+				}
+			},
+			_ => {},
+		}
+	}
+
+	matching
+}
+
+pub fn unmatched_jump(items: &[Item]) -> Vec<Finding> {
+	matching_jumps(items)
+		.into_iter()
+		.enumerate()
+		.filter_map(|(i, matching)| {
+			let message = match (items[i], matching) {
+				(Item::Body(BodyTok::JumpRight), None) => {
+					"`~` without matching `-`"
+				},
+				(Item::Body(BodyTok::JumpLeft), None) => {
+					"`-` without matching `~`"
+				},
+				_ => return None,
+			};
+
+			Some(Finding::new(
+				Rule::UnmatchedJump,
+				i..i + 1,
+				message,
+			))
+		})
+		.collect()
+}
+
+pub fn cancel_pair(items: &[Item]) -> Vec<Finding> {
+	let mut findings = vec![];
+	let mut i = 0;
+
+	while i + 1 < items.len() {
+		let pair = match (items[i], items[i + 1]) {
+			(Item::Head(HeadTok::Inc), Item::Head(HeadTok::Dec)) => "냥냐",
+			(Item::Head(HeadTok::Dec), Item::Head(HeadTok::Inc)) => "냐냥",
+			(Item::Tail(TailTok::Right), Item::Tail(TailTok::Left)) => "?!",
+			(Item::Tail(TailTok::Left), Item::Tail(TailTok::Right)) => "!?",
+			_ => {
+				i += 1;
+				continue;
+			},
+		};
+
+		findings.push(Finding::new(
+			Rule::CancelPair,
+			i..i + 2,
+			format!("`{pair}` cancel each other out"),
+		));
+		i += 2;
+	}
+
+	findings
+}
+
+pub fn empty_loop(items: &[Item]) -> Vec<Finding> {
+	items
+		.windows(2)
+		.enumerate()
+		.filter(|(_, pair)| {
+			matches!(
+				pair,
+				[
+					Item::Body(BodyTok::JumpRight),
+					Item::Body(BodyTok::JumpLeft)
+				]
+			)
+		})
+		.map(|(i, _)| {
+			Finding::new(
+				Rule::EmptyLoop,
+				i..i + 2,
+				"`~-` never ends once entered",
+			)
+		})
+		.collect()
+}
+
+/// Loops starting right after another loop closes, where the current cell
+/// is known to be zero. Comments in between are ignored.
+pub fn dead_loop(items: &[Item]) -> Vec<Finding> {
+	let matching = matching_jumps(items);
+	let mut findings = vec![];
+	let mut previous: Option<usize> = None;
+
+	for (i, item) in items.iter().enumerate() {
+		if let Item::Comment(_) = item {
+			continue;
+		}
+
+		let after_loop = previous.is_some_and(|p| {
+			items[p] == Item::Body(&BodyTok::JumpLeft) && matching[p].is_some()
+		});
+		if let (Item::Body(BodyTok::JumpRight), Some(close), true) =
+			(item, matching[i], after_loop)
+		{
+			findings.push(Finding::new(
+				Rule::DeadLoop,
+				i..close + 1,
+				"loop right after another loop is never entered",
+			));
+		}
+
+		previous = Some(i);
+	}
+
+	findings
+}
+
+pub fn debug_symbol(items: &[Item]) -> Vec<Finding> {
+	items
+		.iter()
+		.enumerate()
+		.filter(|(_, item)| matches!(item, Item::Head(HeadTok::Debug)))
+		.map(|(i, _)| {
+			Finding::new(
+				Rule::DebugSymbol,
+				i..i + 1,
+				"debug symbol `뀨` left in the code",
+			)
+		})
+		.collect()
+}
+
+/// Net pointer movement of `items[start..end]`, if it does not depend on
+/// how many times the loops inside are run.
+fn net_move(
+	items: &[Item],
+	matching: &[Option<usize>],
+	start: usize,
+	end: usize,
+) -> Option<isize> {
+	let mut offset = 0;
+	let mut i = start;
+
+	while i < end {
+		match items[i] {
+			Item::Tail(TailTok::Right) => offset += 1,
+			Item::Tail(TailTok::Left) => offset -= 1,
+			Item::Body(BodyTok::JumpRight) => {
+				let close = matching[i]?;
+
+				if net_move(items, matching, i + 1, close)? != 0 {
+					return None;
+				}
+				i = close;
+			},
+			_ => {},
+		}
+
+		i += 1;
+	}
+
+	Some(offset)
+}
+
+/// Position of the pointer right before every item, whenever it is
+/// known for sure, along with the depth of loops the item is in.
+///
+/// Loops whose body brings the pointer back to where it started keep the
+/// position known, both inside and after them. Any other loop makes it
+/// unknown for the rest of the code.
+pub fn pointer_positions(items: &[Item]) -> Vec<(Option<isize>, usize)> {
+	let matching = matching_jumps(items);
+	let mut positions = Vec::with_capacity(items.len());
+	let mut position = Some(0);
+	let mut depth = 0_usize;
+
+	for (i, item) in items.iter().enumerate() {
+		positions.push((position, depth));
+
+		match item {
+			Item::Tail(TailTok::Right) => position = position.map(|p| p + 1),
+			Item::Tail(TailTok::Left) => position = position.map(|p| p - 1),
+			Item::Body(BodyTok::JumpRight) => {
+				depth += 1;
+				if matching[i]
+					.and_then(|close| net_move(items, &matching, i + 1, close))
+					!= Some(0)
+				{
+					position = None;
+				}
+			},
+			Item::Body(BodyTok::JumpLeft) => depth = depth.saturating_sub(1),
+			_ => {},
+		}
+	}
+
+	positions
+}
+
+/// Reports the first move to the left of the first cell outside of loops.
+///
+/// Moves inside loops are not reported since the loop might never be
+/// entered.
+pub fn pointer_underflow(items: &[Item]) -> Vec<Finding> {
+	pointer_positions(items)
+		.into_iter()
+		.enumerate()
+		.find(|&(i, (position, depth))| {
+			items[i] == Item::Tail(&TailTok::Left)
+				&& position == Some(0)
+				&& depth == 0
+		})
+		.map(|(i, _)| {
+			Finding::new(
+				Rule::PointerUnderflow,
+				i..i + 1,
+				"the pointer moves to the left of the first cell",
+			)
+		})
+		.into_iter()
+		.collect()
+}
+
+#[cfg(test)]
+#[path = "rules.spec.rs"]
+mod tests;
@@ -0,0 +1,106 @@
+use super::*;
+use crate::{lint_code, Config, Severity};
+use pretty_assertions::assert_eq;
+
+fn lint(code: &str) -> Vec<(Rule, &str)> {
+	lint_code(code, &Config::default())
+		.unwrap()
+		.into_iter()
+		.map(|d| (d.rule, &code[d.span.start..d.span.end]))
+		.collect()
+}
+
+#[test]
+fn clean_code_must_have_no_findings() {
+	assert_eq!(
+		lint(include_str!("../../samples/hello.nyan")),
+		[]
+	)
+}
+
+#[test]
+fn unmatched_jumps_must_be_reported() {
+	assert_eq!(
+		lint("-\n~냐-~"),
+		[(Rule::UnmatchedJump, "-"), (Rule::UnmatchedJump, "~"),]
+	)
+}
+
+#[test]
+fn cancelling_pairs_must_be_reported() {
+	assert_eq!(
+		lint("냥냐냥 .?!?"),
+		[(Rule::CancelPair, "냥냐"), (Rule::CancelPair, "?!"),]
+	)
+}
+
+#[test]
+fn pairs_split_by_comments_must_not_be_reported() {
+	assert_eq!(lint("냥\"주석\"냐"), [])
+}
+
+#[test]
+fn pairs_across_lines_must_be_reported() {
+	assert_eq!(
+		lint("냥?\n!냐"),
+		[(Rule::CancelPair, "?\n!")]
+	)
+}
+
+#[test]
+fn empty_loops_must_be_reported() {
+	assert_eq!(
+		lint("냥~ -"),
+		[(Rule::EmptyLoop, "~ -")]
+	)
+}
+
+#[test]
+fn debug_symbols_must_be_reported() {
+	assert_eq!(
+		lint("냥뀨?뀨"),
+		[(Rule::DebugSymbol, "뀨"), (Rule::DebugSymbol, "뀨"),]
+	)
+}
+
+#[test]
+fn pointer_underflow_must_be_reported_once() {
+	assert_eq!(
+		lint("??~냐!냥?-!!!!"),
+		[(Rule::PointerUnderflow, "!")]
+	)
+}
+
+#[test]
+fn pointer_underflow_inside_loops_must_not_be_reported() {
+	assert_eq!(lint("~!냥?-"), [])
+}
+
+#[test]
+fn pointer_underflow_after_unbalanced_loops_must_not_be_reported() {
+	assert_eq!(lint("~?-!"), [])
+}
+
+#[test]
+fn severities_must_follow_rules() {
+	assert_eq!(
+		lint_code("~ 뀨", &Config::default())
+			.unwrap()
+			.into_iter()
+			.map(|d| d.severity)
+			.collect::<Vec<_>>(),
+		[Severity::Error, Severity::Warning]
+	)
+}
+
+#[test]
+fn loops_right_after_loops_must_be_reported() {
+	assert_eq!(
+		lint("~냐-\"주석\"~.-~-"),
+		[
+			(Rule::DeadLoop, "~.-"),
+			(Rule::EmptyLoop, "~-"),
+			(Rule::DeadLoop, "~-"),
+		]
+	)
+}
@@ -0,0 +1,391 @@
+use std::ops::Range as Items;
+
+use lsp_types::{
+	Diagnostic, DiagnosticSeverity, DocumentHighlight, FoldingRange,
+	NumberOrString, Position, Range, SemanticToken, TextEdit,
+};
+
+use crate::{
+	format_code, format_code_range,
+	lint::{items, lint, matching_jumps, Item},
+	parse_code_recovering_with_spans,
+	parser::{ast::*, visit::Visitor},
+	translate::word_to_brainfuck,
+	Config, Severity, Span,
+};
+
+/// Position of the byte offset, with columns counted in UTF-16 code units.
+pub fn position(text: &str, offset: usize) -> Position {
+	let before = &text[..offset];
+	let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+	Position::new(
+		before.matches('\n').count() as u32,
+		before[line_start..]
+			.encode_utf16()
+			.count() as u32,
+	)
+}
+
+/// Byte offset of the position, clamped to the end of its line.
+pub fn offset(text: &str, position: Position) -> usize {
+	let line_start = match position.line {
+		0 => 0,
+		line => text
+			.match_indices('\n')
+			.nth(line as usize - 1)
+			.map_or(text.len(), |(i, _)| i + 1),
+	};
+	let line = &text[line_start..];
+	let line = &line[..line.find('\n').unwrap_or(line.len())];
+	let mut units = 0;
+
+	for (i, ch) in line.char_indices() {
+		if units >= position.character as usize {
+			return line_start + i;
+		}
+		units += ch.len_utf16();
+	}
+
+	line_start + line.len()
+}
+
+pub fn range(text: &str, span: Span) -> Range {
+	Range::new(
+		position(text, span.start),
+		position(text, span.end),
+	)
+}
+
+fn token_count(word: &Word) -> usize {
+	word.head
+		.as_ref()
+		.map_or(0, |Head(v)| v.len())
+		+ word
+			.body
+			.as_ref()
+			.map_or(0, |Body(v)| v.len())
+		+ word
+			.tail
+			.as_ref()
+			.map_or(0, |Tail(v)| v.len())
+}
+
+/// Every word of the program, along with the items of its tokens.
+fn words(Root(code): &Root) -> Vec<(Items<usize>, &Word)> {
+	let mut words = vec![];
+	let mut start = 0;
+	let sentences = code
+		.leading_sentences
+		.iter()
+		.map(|sentence| (0, sentence))
+		.chain(code.paragraphs.iter().flat_map(
+			|Paragraph(comments, sentences)| {
+				sentences
+					.iter()
+					.enumerate()
+					.map(move |(i, sentence)| {
+						(
+							if i == 0 { comments.len() } else { 0 },
+							sentence,
+						)
+					})
+			},
+		));
+
+	for (comments, Sentence(sentence)) in sentences {
+		start += comments;
+		for word in sentence {
+			let end = start + token_count(word);
+			words.push((start..end, word));
+			start = end + word.comments.len();
+		}
+	}
+
+	words
+}
+
+fn sentence_token_count(sentences: &[Sentence]) -> usize {
+	sentences
+		.iter()
+		.flat_map(|Sentence(words)| words)
+		.map(|word| token_count(word) + word.comments.len())
+		.sum()
+}
+
+/// Items of every paragraph, from its first comment to its last sentence.
+fn paragraphs(Root(code): &Root) -> Vec<Items<usize>> {
+	let mut start = sentence_token_count(&code.leading_sentences);
+
+	code.paragraphs
+		.iter()
+		.map(|Paragraph(comments, sentences)| {
+			let end = start + comments.len() + sentence_token_count(sentences);
+			let items = start..end;
+			start = end;
+
+			items
+		})
+		.collect()
+}
+
+/// Index of the item's semantic token type in [`TOKEN_TYPES`].
+///
+/// [`TOKEN_TYPES`]: super::TOKEN_TYPES
+fn token_type(item: Item) -> u32 {
+	match item {
+		Item::Head(HeadTok::Inc | HeadTok::Dec) => 0,
+		Item::Body(BodyTok::Out | BodyTok::In) => 1,
+		Item::Body(BodyTok::JumpRight | BodyTok::JumpLeft) => 2,
+		Item::Tail(_) => 3,
+		Item::Head(HeadTok::Debug) => 4,
+		Item::Comment(_) => 5,
+	}
+}
+
+/// Collects the code skipped by the recovering parser.
+struct Skipped<'a>(Vec<&'a Invalid>);
+
+impl<'a> Visitor<'a> for Skipped<'a> {
+	fn visit_invalid(&mut self, invalid: &'a Invalid) {
+		self.0.push(invalid);
+	}
+}
+
+/// An open document and what the server knows about it.
+pub struct Document {
+	pub text: String,
+	pub config: Config,
+}
+
+impl Document {
+	/// Parses the text, handling unknown characters as the configuration
+	/// tells and keeping the code that fails to parse in the tree.
+	fn parse(&self) -> (Root, Vec<Span>) {
+		parse_code_recovering_with_spans(
+			&self.text,
+			self.config.format.unknown_chars,
+		)
+	}
+
+	pub fn diagnostics(&self) -> Vec<Diagnostic> {
+		let (root, spans) = self.parse();
+		let mut skipped = Skipped(vec![]);
+		skipped.visit_root(&root);
+
+		let errors = skipped
+			.0
+			.into_iter()
+			.map(|invalid| Diagnostic {
+				range: range(&self.text, invalid.span),
+				severity: Some(DiagnosticSeverity::ERROR),
+				source: Some(String::from("nyanfmt")),
+				message: invalid.error.to_string(),
+				..Default::default()
+			});
+
+		let lints = lint(&root, &spans, &self.config)
+			.into_iter()
+			.map(|diagnostic| Diagnostic {
+				range: range(&self.text, diagnostic.span),
+				severity: Some(match diagnostic.severity {
+					Severity::Warning => DiagnosticSeverity::WARNING,
+					Severity::Error => DiagnosticSeverity::ERROR,
+				}),
+				code: Some(NumberOrString::String(
+					diagnostic.rule.id().to_string(),
+				)),
+				source: Some(String::from("nyanfmt")),
+				message: diagnostic.message,
+				..Default::default()
+			});
+
+		errors.chain(lints).collect()
+	}
+
+	/// Edit replacing the whole document with its formatted version, if it
+	/// can be parsed and is not formatted yet.
+	pub fn format(&self) -> Option<Vec<TextEdit>> {
+		let formatted = format_code(&self.text, &self.config).ok()?;
+
+		Some(if formatted == self.text {
+			vec![]
+		} else {
+			vec![TextEdit::new(
+				range(
+					&self.text,
+					Span::new(0, self.text.len()),
+				),
+				formatted,
+			)]
+		})
+	}
+
+	pub fn format_range(&self, selection: Range) -> Option<Vec<TextEdit>> {
+		let span = Span::new(
+			offset(&self.text, selection.start),
+			offset(&self.text, selection.end),
+		);
+
+		Some(
+			format_code_range(&self.text, span, &self.config)
+				.ok()?
+				.into_iter()
+				.map(|edit| {
+					TextEdit::new(
+						range(&self.text, edit.span),
+						edit.new_text,
+					)
+				})
+				.collect(),
+		)
+	}
+
+	/// Index of the item at the position, preferring the one starting there
+	/// over the one ending there.
+	fn item_at(spans: &[Span], offset: usize) -> Option<usize> {
+		spans
+			.iter()
+			.position(|span| span.start <= offset && offset < span.end)
+			.or_else(|| {
+				spans
+					.iter()
+					.position(|span| span.end == offset)
+			})
+	}
+
+	/// The word at the position, along with its Brainfuck equivalent.
+	pub fn hover(&self, position: Position) -> Option<(Range, String)> {
+		let (root, spans) = self.parse();
+		let item = Self::item_at(&spans, offset(&self.text, position))?;
+		let (items, word) = words(&root)
+			.into_iter()
+			.find(|(items, _)| items.contains(&item))?;
+
+		Some((
+			range(
+				&self.text,
+				Span::new(
+					spans[items.start].start,
+					spans[items.end - 1].end,
+				),
+			),
+			word_to_brainfuck(word),
+		))
+	}
+
+	/// The `~` or `-` at the position and its matching jump.
+	pub fn matching_jumps(
+		&self,
+		position: Position,
+	) -> Option<Vec<DocumentHighlight>> {
+		let (root, spans) = self.parse();
+		let items = items(&root);
+		let item = Self::item_at(&spans, offset(&self.text, position))?;
+
+		if !matches!(
+			items[item],
+			Item::Body(BodyTok::JumpRight | BodyTok::JumpLeft)
+		) {
+			return None;
+		}
+		let matching = matching_jumps(&items)[item]?;
+
+		Some(
+			[item, matching]
+				.map(|i| DocumentHighlight {
+					range: range(&self.text, spans[i]),
+					kind: None,
+				})
+				.to_vec(),
+		)
+	}
+
+	/// Semantic tokens of the document, encoded relative to each other.
+	///
+	/// Adjacent tokens of the same type are merged, and comments spanning
+	/// several lines are split into one token per line.
+	pub fn semantic_tokens(&self) -> Option<Vec<SemanticToken>> {
+		let (root, spans) = self.parse();
+		let mut runs: Vec<(Span, u32)> = vec![];
+
+		for (item, span) in items(&root).into_iter().zip(spans) {
+			let token_type = token_type(item);
+
+			match runs.last_mut() {
+				Some((run, run_type))
+					if *run_type == token_type && run.end == span.start =>
+				{
+					run.end = span.end
+				},
+				_ => runs.push((span, token_type)),
+			}
+		}
+
+		let mut tokens = vec![];
+		let mut last = Position::new(0, 0);
+
+		for (span, token_type) in runs {
+			let mut start = span.start;
+
+			for line in self.text[span.start..span.end].split_inclusive('\n') {
+				let at = position(&self.text, start);
+				let length = line
+					.trim_end_matches('\n')
+					.trim_end_matches('\r')
+					.encode_utf16()
+					.count();
+				start += line.len();
+
+				if length == 0 {
+					continue;
+				}
+				tokens.push(SemanticToken {
+					delta_line: at.line - last.line,
+					delta_start: if at.line == last.line {
+						at.character - last.character
+					} else {
+						at.character
+					},
+					length: length as u32,
+					token_type,
+					token_modifiers_bitset: 0,
+				});
+				last = at;
+			}
+		}
+
+		Some(tokens)
+	}
+
+	/// Foldable paragraphs and loops, when they span several lines.
+	pub fn folding_ranges(&self) -> Option<Vec<FoldingRange>> {
+		let (root, spans) = self.parse();
+		let items = items(&root);
+		let loops = matching_jumps(&items)
+			.into_iter()
+			.enumerate()
+			.filter_map(|(start, end)| Some(start..end? + 1))
+			.filter(|jumps| jumps.start < jumps.end);
+		let mut ranges = paragraphs(&root)
+			.into_iter()
+			.chain(loops)
+			.filter_map(|items| {
+				let start = position(&self.text, spans[items.start].start);
+				let end = position(&self.text, spans[items.end - 1].end);
+
+				(start.line < end.line).then(|| FoldingRange {
+					start_line: start.line,
+					start_character: Some(start.character),
+					end_line: end.line,
+					end_character: Some(end.character),
+					..Default::default()
+				})
+			})
+			.collect::<Vec<_>>();
+
+		ranges.sort_by_key(|range| (range.start_line, range.end_line));
+
+		Some(ranges)
+	}
+}
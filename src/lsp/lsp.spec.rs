@@ -0,0 +1,520 @@
+use super::*;
+use lsp_server::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::{json, Value};
+use std::{
+	thread::{self, JoinHandle},
+	time::Duration,
+};
+
+const URI: &str = "untitled:test.nyan";
+
+/// Client sending scripted messages to a server running on another thread.
+struct Client {
+	connection: Connection,
+	server: JoinHandle<Result<(), Error>>,
+	id: i32,
+}
+
+impl Client {
+	fn start() -> Self {
+		let (server, connection) = Connection::memory();
+		let mut client = Self {
+			connection,
+			server: thread::spawn(move || serve(&server)),
+			id: 0,
+		};
+
+		let result = client.request(
+			"initialize",
+			json!({ "capabilities": {} }),
+		);
+		assert_eq!(
+			result["capabilities"]["documentFormattingProvider"],
+			json!(true)
+		);
+		client.notify("initialized", json!({}));
+
+		client
+	}
+
+	fn receive(&self) -> Message {
+		self.connection
+			.receiver
+			.recv_timeout(Duration::from_secs(5))
+			.unwrap()
+	}
+
+	/// Sends the request, returning the result or the error of the response.
+	fn request(&mut self, method: &str, params: Value) -> Value {
+		self.id += 1;
+		self.connection
+			.sender
+			.send(
+				Request::new(
+					RequestId::from(self.id),
+					method.to_string(),
+					params,
+				)
+				.into(),
+			)
+			.unwrap();
+
+		match self.receive() {
+			Message::Response(response) => {
+				assert_eq!(response.id, RequestId::from(self.id));
+				match response.error {
+					Some(error) => json!({ "code": error.code }),
+					None => response.result.unwrap(),
+				}
+			},
+			message => panic!("expected a response, got {message:?}"),
+		}
+	}
+
+	fn notify(&self, method: &str, params: Value) {
+		self.connection
+			.sender
+			.send(Notification::new(method.to_string(), params).into())
+			.unwrap();
+	}
+
+	/// Opens the document, returning the diagnostics published for it.
+	fn open(&self, text: &str) -> Value {
+		self.open_at(URI, text);
+		self.diagnostics()
+	}
+
+	fn open_at(&self, uri: &str, text: &str) {
+		self.notify(
+			"textDocument/didOpen",
+			json!({
+				"textDocument": {
+					"uri": uri,
+					"languageId": "nyanlang",
+					"version": 1,
+					"text": text,
+				}
+			}),
+		);
+	}
+
+	/// Receives a notification, returning its parameters.
+	fn notification(&self, method: &str) -> Value {
+		match self.receive() {
+			Message::Notification(notification) => {
+				assert_eq!(notification.method, method);
+				notification.params
+			},
+			message => panic!("expected {method}, got {message:?}"),
+		}
+	}
+
+	fn diagnostics(&self) -> Value {
+		self.diagnostics_at(URI)
+	}
+
+	fn diagnostics_at(&self, uri: &str) -> Value {
+		let params = self.notification("textDocument/publishDiagnostics");
+		assert_eq!(params["uri"], json!(uri));
+		params["diagnostics"].clone()
+	}
+
+	fn at(&mut self, method: &str, line: u32, character: u32) -> Value {
+		self.request(
+			method,
+			json!({
+				"textDocument": { "uri": URI },
+				"position": { "line": line, "character": character },
+			}),
+		)
+	}
+
+	fn shutdown(mut self) {
+		assert_eq!(
+			self.request("shutdown", Value::Null),
+			Value::Null
+		);
+		self.notify("exit", Value::Null);
+		assert_eq!(self.server.join().unwrap(), Ok(()));
+	}
+}
+
+/// Writes the configuration and a document next to it in a directory with
+/// a space in its name, returning the `file:` URI of the document.
+fn file_uri(dir: &str, config: &str) -> String {
+	let dir = std::env::temp_dir()
+		.join(dir)
+		.join("my dir");
+	let file = dir.join("파일.nyan");
+	std::fs::create_dir_all(&dir).unwrap();
+	std::fs::write(dir.join(Config::FILE_NAME), config).unwrap();
+	std::fs::write(&file, "냥냐").unwrap();
+
+	let mut uri = String::from("file://");
+	for byte in file.to_str().unwrap().bytes() {
+		match byte {
+			b'/' | b'-' | b'.' | b'_' | b'~' => uri.push(byte as char),
+			_ if byte.is_ascii_alphanumeric() => uri.push(byte as char),
+			_ => uri.push_str(&format!("%{byte:02X}")),
+		}
+	}
+
+	uri
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Value {
+	json!({
+		"start": { "line": start.0, "character": start.1 },
+		"end": { "line": end.0, "character": end.1 },
+	})
+}
+
+#[test]
+fn lint_findings_must_be_published_on_open_and_change() {
+	let client = Client::start();
+
+	assert_eq!(
+		client.open("냥냐 ~"),
+		json!([
+			{
+				"range": range((0, 0), (0, 2)),
+				"severity": 2,
+				"code": "cancel-pair",
+				"source": "nyanfmt",
+				"message": "`냥냐` cancel each other out",
+			},
+			{
+				"range": range((0, 3), (0, 4)),
+				"severity": 1,
+				"code": "unmatched-jump",
+				"source": "nyanfmt",
+				"message": "`~` without matching `-`",
+			},
+		])
+	);
+
+	client.notify(
+		"textDocument/didChange",
+		json!({
+			"textDocument": { "uri": URI, "version": 2 },
+			"contentChanges": [{ "text": "냥" }],
+		}),
+	);
+	assert_eq!(client.diagnostics(), json!([]));
+
+	client.shutdown()
+}
+
+#[test]
+fn config_must_be_discovered_next_to_percent_encoded_paths() {
+	let client = Client::start();
+	let uri = file_uri(
+		"nyanfmt-lsp-config",
+		"[lint]\ncancel-pair = \"off\"",
+	);
+	assert!(uri.ends_with("/my%20dir/%ED%8C%8C%EC%9D%BC.nyan"));
+
+	client.open_at(&uri, "냥냐");
+	assert_eq!(client.diagnostics_at(&uri), json!([]));
+
+	client.shutdown()
+}
+
+#[test]
+fn config_errors_must_be_shown_and_defaults_used() {
+	let client = Client::start();
+	let uri = file_uri(
+		"nyanfmt-lsp-broken-config",
+		"[lint]\ncancel-pair = \"loud\"",
+	);
+
+	client.open_at(&uri, "냥냐");
+	let message = client.notification("window/showMessage");
+	assert_eq!(message["type"], json!(2));
+	assert!(message["message"]
+		.as_str()
+		.unwrap()
+		.starts_with("Can't load the configuration: "));
+	assert_eq!(
+		client.diagnostics_at(&uri)[0]["code"],
+		json!("cancel-pair")
+	);
+
+	client.shutdown()
+}
+
+#[test]
+fn malformed_notifications_must_be_logged_and_skipped() {
+	let mut client = Client::start();
+	client.open("냥");
+
+	client.notify(
+		"textDocument/didChange",
+		json!({ "contentChanges": 1 }),
+	);
+	assert_eq!(
+		client.notification("window/logMessage")["type"],
+		json!(1)
+	);
+	assert_eq!(
+		client.request(
+			"textDocument/formatting",
+			json!({
+				"textDocument": { "uri": URI },
+				"options": { "tabSize": 4, "insertSpaces": false },
+			})
+		),
+		json!([{ "range": range((0, 0), (0, 1)), "newText": "냥\n" }])
+	);
+
+	client.shutdown()
+}
+
+#[test]
+fn syntax_errors_must_be_published_at_their_position() {
+	let client = Client::start();
+
+	assert_eq!(
+		client.open("냥\n 냐%"),
+		json!([
+			{
+				"range": range((1, 2), (1, 3)),
+				"severity": 1,
+				"source": "nyanfmt",
+				"message": "failed to tokenize the code",
+			},
+			{
+				"range": range((0, 0), (1, 2)),
+				"severity": 2,
+				"code": "cancel-pair",
+				"source": "nyanfmt",
+				"message": "`냥냐` cancel each other out",
+			},
+		])
+	);
+
+	client.shutdown()
+}
+
+#[test]
+fn code_after_a_syntax_error_must_still_be_linted() {
+	let client = Client::start();
+
+	assert_eq!(
+		client.open("냥 %%\n~"),
+		json!([
+			{
+				"range": range((0, 2), (0, 4)),
+				"severity": 1,
+				"source": "nyanfmt",
+				"message": "failed to tokenize the code",
+			},
+			{
+				"range": range((1, 0), (1, 1)),
+				"severity": 1,
+				"code": "unmatched-jump",
+				"source": "nyanfmt",
+				"message": "`~` without matching `-`",
+			},
+		])
+	);
+
+	client.shutdown()
+}
+
+#[test]
+fn documents_must_be_formatted() {
+	let mut client = Client::start();
+	client.open("냥  냥 ?\n\n\"주석\"\n냐   .");
+
+	assert_eq!(
+		client.request(
+			"textDocument/formatting",
+			json!({
+				"textDocument": { "uri": URI },
+				"options": { "tabSize": 4, "insertSpaces": false },
+			})
+		),
+		json!([{
+			"range": range((0, 0), (3, 5)),
+			"newText": "냥냥?\n\n\"주석\"\n냐.\n",
+		}])
+	);
+	assert_eq!(
+		client.request(
+			"textDocument/rangeFormatting",
+			json!({
+				"textDocument": { "uri": URI },
+				"range": range((3, 0), (3, 0)),
+				"options": { "tabSize": 4, "insertSpaces": false },
+			})
+		),
+		json!([
+			{
+				"range": range((3, 0), (3, 5)),
+				"newText": "냐.",
+			},
+			{
+				"range": range((3, 5), (3, 5)),
+				"newText": "\n",
+			},
+		])
+	);
+
+	client.shutdown()
+}
+
+#[test]
+fn hover_must_show_the_brainfuck_equivalent_of_the_word() {
+	let mut client = Client::start();
+	client.open("\"주석\"\n냥냥?? ,~뀨");
+
+	assert_eq!(
+		client.at("textDocument/hover", 1, 1),
+		json!({
+			"contents": {
+				"kind": "markdown",
+				"value": "```brainfuck\n++>>\n```",
+			},
+			"range": range((1, 0), (1, 4)),
+		})
+	);
+	assert_eq!(
+		client.at("textDocument/hover", 1, 6),
+		json!({
+			"contents": {
+				"kind": "markdown",
+				"value": "```brainfuck\n,[\n```",
+			},
+			"range": range((1, 5), (1, 7)),
+		})
+	);
+	assert_eq!(
+		client.at("textDocument/hover", 0, 1),
+		Value::Null
+	);
+
+	client.shutdown()
+}
+
+#[test]
+fn matching_jumps_must_be_highlighted() {
+	let mut client = Client::start();
+	client.open("~냥 ~?- -");
+
+	assert_eq!(
+		client.at("textDocument/documentHighlight", 0, 0),
+		json!([
+			{ "range": range((0, 0), (0, 1)) },
+			{ "range": range((0, 7), (0, 8)) },
+		])
+	);
+	assert_eq!(
+		client.at("textDocument/documentHighlight", 0, 5),
+		json!([
+			{ "range": range((0, 5), (0, 6)) },
+			{ "range": range((0, 3), (0, 4)) },
+		])
+	);
+	assert_eq!(
+		client.at("textDocument/documentHighlight", 0, 1),
+		Value::Null
+	);
+
+	client.shutdown()
+}
+
+#[test]
+fn unknown_requests_must_be_rejected() {
+	let mut client = Client::start();
+
+	assert_eq!(
+		client.request("textDocument/completion", json!({})),
+		json!({ "code": ErrorCode::MethodNotFound as i32 })
+	);
+
+	client.shutdown()
+}
+
+#[test]
+fn tokens_must_be_classified() {
+	let mut client = Client::start();
+	client.open("\"주석\"\n냥냥~뀨 ?-\n\"줄\n바꿈\"\n냐.!");
+
+	assert_eq!(
+		client.request(
+			"textDocument/semanticTokens/full",
+			json!({ "textDocument": { "uri": URI } })
+		),
+		json!({
+			"data": [
+				0, 0, 4, 5, 0,
+				1, 0, 2, 0, 0,
+				0, 2, 1, 2, 0,
+				0, 1, 1, 4, 0,
+				0, 2, 1, 3, 0,
+				0, 1, 1, 2, 0,
+				1, 0, 2, 5, 0,
+				1, 0, 3, 5, 0,
+				1, 0, 1, 0, 0,
+				0, 1, 1, 1, 0,
+				0, 1, 1, 3, 0,
+			]
+		})
+	);
+
+	client.shutdown()
+}
+
+#[test]
+fn comment_lines_must_not_count_carriage_returns() {
+	let mut client = Client::start();
+	client.open("\"줄\r\n바꿈\"\r\n냐");
+
+	assert_eq!(
+		client.request(
+			"textDocument/semanticTokens/full",
+			json!({ "textDocument": { "uri": URI } })
+		),
+		json!({
+			"data": [
+				0, 0, 2, 5, 0,
+				1, 0, 3, 5, 0,
+				1, 0, 1, 0, 0,
+			]
+		})
+	);
+
+	client.shutdown()
+}
+
+#[test]
+fn paragraphs_and_loops_over_several_lines_must_fold() {
+	let mut client = Client::start();
+	client.open("냥~\n?-\n\n\"문단\"\n~냥\n-\n~-");
+
+	let folds = client.request(
+		"textDocument/foldingRange",
+		json!({ "textDocument": { "uri": URI } }),
+	);
+
+	assert_eq!(
+		folds
+			.as_array()
+			.unwrap()
+			.iter()
+			.map(|fold| (
+				fold["startLine"].clone(),
+				fold["endLine"].clone()
+			))
+			.collect::<Vec<_>>(),
+		[
+			(json!(0), json!(1)),
+			(json!(3), json!(6)),
+			(json!(4), json!(5)),
+		]
+	);
+
+	client.shutdown()
+}
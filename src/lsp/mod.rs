@@ -0,0 +1,357 @@
+mod document;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use lsp_server::{
+	Connection, ErrorCode, Message, Notification, Request, Response,
+};
+use lsp_types::{
+	notification::{
+		DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+		LogMessage, Notification as _, PublishDiagnostics, ShowMessage,
+	},
+	request::{
+		DocumentHighlightRequest, FoldingRangeRequest, Formatting,
+		HoverRequest, RangeFormatting, Request as _, SemanticTokensFullRequest,
+	},
+	DocumentFormattingParams, DocumentHighlightParams,
+	DocumentRangeFormattingParams, FoldingRangeParams,
+	FoldingRangeProviderCapability, Hover, HoverContents, HoverParams,
+	HoverProviderCapability, LogMessageParams, MarkupContent, MarkupKind,
+	MessageType, OneOf, PublishDiagnosticsParams, SemanticTokenType,
+	SemanticTokens, SemanticTokensFullOptions, SemanticTokensLegend,
+	SemanticTokensOptions, SemanticTokensParams,
+	SemanticTokensServerCapabilities, ServerCapabilities, ShowMessageParams,
+	TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{Config, Error};
+use document::Document;
+
+fn protocol_error(err: impl ToString) -> Error {
+	Error::Protocol(err.to_string())
+}
+
+/// Semantic token types of head, body, jump, tail, debug and comment
+/// tokens, picked among the standard ones so that editors colour them
+/// without extra configuration.
+const TOKEN_TYPES: [SemanticTokenType; 6] = [
+	SemanticTokenType::OPERATOR,
+	SemanticTokenType::FUNCTION,
+	SemanticTokenType::KEYWORD,
+	SemanticTokenType::VARIABLE,
+	SemanticTokenType::MACRO,
+	SemanticTokenType::COMMENT,
+];
+
+fn capabilities() -> ServerCapabilities {
+	ServerCapabilities {
+		text_document_sync: Some(TextDocumentSyncCapability::Kind(
+			TextDocumentSyncKind::FULL,
+		)),
+		document_formatting_provider: Some(OneOf::Left(true)),
+		document_range_formatting_provider: Some(OneOf::Left(true)),
+		hover_provider: Some(HoverProviderCapability::Simple(true)),
+		document_highlight_provider: Some(OneOf::Left(true)),
+		semantic_tokens_provider: Some(
+			SemanticTokensServerCapabilities::SemanticTokensOptions(
+				SemanticTokensOptions {
+					legend: SemanticTokensLegend {
+						token_types: TOKEN_TYPES.to_vec(),
+						token_modifiers: vec![],
+					},
+					full: Some(SemanticTokensFullOptions::Bool(true)),
+					..Default::default()
+				},
+			),
+		),
+		folding_range_provider: Some(FoldingRangeProviderCapability::Simple(
+			true,
+		)),
+		..Default::default()
+	}
+}
+
+/// Path of the file the URI points to, with its percent-encoded
+/// characters decoded, or `None` if it is not a `file:` URI.
+fn file_path(uri: &Uri) -> Result<Option<PathBuf>, Error> {
+	if uri
+		.scheme()
+		.map(|scheme| scheme.as_str())
+		!= Some("file")
+	{
+		return Ok(None);
+	}
+
+	let path = uri
+		.path()
+		.as_estr()
+		.decode()
+		.into_string()
+		.map_err(|_| {
+			protocol_error(format!(
+				"invalid path in {}",
+				uri.as_str()
+			))
+		})?;
+
+	Ok(Some(PathBuf::from(path.as_ref())))
+}
+
+/// Configuration for the document, read from the `nyanfmt.toml` closest to
+/// it if it is a file.
+fn config(uri: &Uri) -> Result<Config, Error> {
+	match file_path(uri)? {
+		Some(path) => Config::discover(&path),
+		None => Ok(Config::default()),
+	}
+}
+
+#[derive(Default)]
+struct Server {
+	documents: HashMap<Uri, Document>,
+}
+
+impl Server {
+	fn document(&self, uri: &Uri) -> Result<&Document, String> {
+		self.documents
+			.get(uri)
+			.ok_or_else(|| format!("unknown document {}", uri.as_str()))
+	}
+
+	fn request(&self, request: Request) -> Response {
+		fn handle<P: DeserializeOwned, R: Serialize>(
+			request: Request,
+			f: impl FnOnce(P) -> Result<R, String>,
+		) -> Response {
+			let result = serde_json::from_value(request.params)
+				.map_err(|err| err.to_string())
+				.and_then(f);
+
+			match result {
+				Ok(result) => Response::new_ok(request.id, result),
+				Err(message) => Response::new_err(
+					request.id,
+					ErrorCode::InvalidParams as i32,
+					message,
+				),
+			}
+		}
+
+		match request.method.as_str() {
+			Formatting::METHOD => handle(
+				request,
+				|params: DocumentFormattingParams| {
+					Ok(self
+						.document(&params.text_document.uri)?
+						.format())
+				},
+			),
+			RangeFormatting::METHOD => handle(
+				request,
+				|params: DocumentRangeFormattingParams| {
+					Ok(self
+						.document(&params.text_document.uri)?
+						.format_range(params.range))
+				},
+			),
+			HoverRequest::METHOD => handle(request, |params: HoverParams| {
+				let position = params.text_document_position_params;
+				let hover = self
+					.document(&position.text_document.uri)?
+					.hover(position.position);
+
+				Ok(hover.map(|(range, brainfuck)| Hover {
+					contents: HoverContents::Markup(MarkupContent {
+						kind: MarkupKind::Markdown,
+						value: format!("```brainfuck\n{brainfuck}\n```"),
+					}),
+					range: Some(range),
+				}))
+			}),
+			DocumentHighlightRequest::METHOD => handle(
+				request,
+				|params: DocumentHighlightParams| {
+					let position = params.text_document_position_params;
+
+					Ok(self
+						.document(&position.text_document.uri)?
+						.matching_jumps(position.position))
+				},
+			),
+			SemanticTokensFullRequest::METHOD => handle(
+				request,
+				|params: SemanticTokensParams| {
+					let tokens = self
+						.document(&params.text_document.uri)?
+						.semantic_tokens();
+
+					Ok(tokens.map(|data| SemanticTokens {
+						result_id: None,
+						data,
+					}))
+				},
+			),
+			FoldingRangeRequest::METHOD => {
+				handle(request, |params: FoldingRangeParams| {
+					Ok(self
+						.document(&params.text_document.uri)?
+						.folding_ranges())
+				})
+			},
+			_ => Response::new_err(
+				request.id,
+				ErrorCode::MethodNotFound as i32,
+				format!("unsupported method {}", request.method),
+			),
+		}
+	}
+
+	/// Keeps track of the open documents, returning the diagnostics to
+	/// publish, after a message for the user if the configuration of an
+	/// opened document can't be loaded.
+	fn notification(
+		&mut self,
+		notification: Notification,
+	) -> Result<Vec<Notification>, Error> {
+		fn params<N: lsp_types::notification::Notification>(
+			notification: Notification,
+		) -> Result<N::Params, Error> {
+			serde_json::from_value(notification.params).map_err(protocol_error)
+		}
+
+		let mut replies = vec![];
+		let uri = match notification.method.as_str() {
+			DidOpenTextDocument::METHOD => {
+				let document =
+					params::<DidOpenTextDocument>(notification)?.text_document;
+				let config = config(&document.uri).unwrap_or_else(|err| {
+					replies.push(show_message(
+						MessageType::WARNING,
+						format!("Can't load the configuration: {err}"),
+					));
+					Config::default()
+				});
+
+				self.documents.insert(
+					document.uri.clone(),
+					Document {
+						text: document.text,
+						config,
+					},
+				);
+				document.uri
+			},
+			DidChangeTextDocument::METHOD => {
+				let mut params = params::<DidChangeTextDocument>(notification)?;
+				let uri = params.text_document.uri;
+				let (Some(document), Some(change)) = (
+					self.documents.get_mut(&uri),
+					params.content_changes.pop(),
+				) else {
+					return Ok(replies);
+				};
+
+				document.text = change.text;
+				uri
+			},
+			DidCloseTextDocument::METHOD => {
+				let uri = params::<DidCloseTextDocument>(notification)?
+					.text_document
+					.uri;
+
+				self.documents.remove(&uri);
+				return Ok(vec![publish(uri, vec![])]);
+			},
+			_ => return Ok(replies),
+		};
+
+		let diagnostics = self.documents[&uri].diagnostics();
+		replies.push(publish(uri, diagnostics));
+
+		Ok(replies)
+	}
+}
+
+fn publish(uri: Uri, diagnostics: Vec<lsp_types::Diagnostic>) -> Notification {
+	Notification::new(
+		PublishDiagnostics::METHOD.to_string(),
+		PublishDiagnosticsParams::new(uri, diagnostics, None),
+	)
+}
+
+fn show_message(typ: MessageType, message: String) -> Notification {
+	Notification::new(
+		ShowMessage::METHOD.to_string(),
+		ShowMessageParams { typ, message },
+	)
+}
+
+fn log_message(typ: MessageType, message: String) -> Notification {
+	Notification::new(
+		LogMessage::METHOD.to_string(),
+		LogMessageParams { typ, message },
+	)
+}
+
+/// Answers the requests of the client on the other end of the connection,
+/// until it shuts the server down.
+fn serve(connection: &Connection) -> Result<(), Error> {
+	let capabilities =
+		serde_json::to_value(capabilities()).map_err(protocol_error)?;
+	connection
+		.initialize(capabilities)
+		.map_err(protocol_error)?;
+
+	let mut server = Server::default();
+
+	for message in &connection.receiver {
+		let replies = match message {
+			Message::Request(request) => {
+				if connection
+					.handle_shutdown(&request)
+					.map_err(protocol_error)?
+				{
+					return Ok(());
+				}
+				vec![server.request(request).into()]
+			},
+			// a notification the server can't read is only logged, as
+			// there is no response to report it in
+			Message::Notification(notification) => server
+				.notification(notification)
+				.unwrap_or_else(|err| {
+					vec![log_message(MessageType::ERROR, err.to_string())]
+				})
+				.into_iter()
+				.map(Message::from)
+				.collect(),
+			Message::Response(_) => vec![],
+		};
+
+		for reply in replies {
+			connection
+				.sender
+				.send(reply)
+				.map_err(protocol_error)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Runs the language server over stdin and stdout.
+pub fn run() -> Result<(), Error> {
+	let (connection, io_threads) = Connection::stdio();
+
+	serve(&connection)?;
+	drop(connection);
+	io_threads.join()?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+#[path = "lsp.spec.rs"]
+mod tests;
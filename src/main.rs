@@ -1,32 +1,375 @@
-use nyanfmt::format;
-use std::fs::{read_to_string, write};
+#[cfg(feature = "serde")]
+use nyanfmt::format_ast_json;
+#[cfg(feature = "lsp")]
+use nyanfmt::run_language_server;
+use nyanfmt::{
+	apply_edits, check_legacy_idempotency, dump_ast, fix_code, format,
+	format_code_range, generate_code, lint_code, run_code, translate_to_wat,
+	verify_formatting, AstFormat, Config, Error, Severity, Span,
+};
+use std::{
+	fmt::Display,
+	fs::{read_to_string, write},
+	io::{read_to_string as read_all, stdin, stdout},
+	path::Path,
+	process::exit,
+};
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(
 	version,
-	about = "Format nyanlang code\n\nBy default, output will be written to stdout.\nUse --write flag to actually save the output."
+	about = "Format nyanlang code\n\nBy default, output will be written to stdout.\nUse --write flag to actually save the output.",
+	args_conflicts_with_subcommands = true,
+	subcommand_negates_reqs = true
 )]
 struct Arg {
-	file: String,
+	#[command(subcommand)]
+	command: Option<Command>,
+
+	#[arg(required = true)]
+	file: Option<String>,
 
 	/// write the formatted result to the file
 	#[arg(short, long)]
 	write: bool,
+
+	/// only format the sentences and comments overlapping these lines,
+	/// one-based and included
+	#[arg(
+		long,
+		value_name = "START:END",
+		value_parser = parse_range,
+		conflicts_with = "check_idempotency"
+	)]
+	range: Option<(usize, usize)>,
+
+	/// check that formatting kept every instruction and comment intact
+	#[arg(long)]
+	verify: bool,
+
+	/// check that formatting the result again does not change it
+	#[arg(long)]
+	check_idempotency: bool,
+
+	/// read a syntax tree in JSON from the file, or from stdin if the file
+	/// is `-`, and print it as code
+	#[cfg(feature = "serde")]
+	#[arg(
+		long,
+		conflicts_with_all = ["write", "range", "verify", "check_idempotency"]
+	)]
+	from_json: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Print the tree nyanlang code is parsed into
+	Ast {
+		file: String,
+
+		/// how to print the tree
+		#[arg(long, value_enum, default_value = "debug")]
+		format: Dump,
+	},
+
+	/// Generate nyanlang code printing the given text
+	Generate {
+		/// text to print, read from stdin if omitted
+		text: Option<String>,
+	},
+
+	/// Report suspicious constructs in nyanlang code
+	Lint {
+		file: String,
+
+		/// fix the findings that can be fixed without changing what the
+		/// code does, writing the fixed code to stdout
+		#[arg(long)]
+		fix: bool,
+
+		/// write the fixed code to the file
+		#[arg(short, long, requires = "fix")]
+		write: bool,
+	},
+
+	/// Run a language server over stdin and stdout
+	#[cfg(feature = "lsp")]
+	Lsp,
+
+	/// Run nyanlang code, reading input from stdin
+	Run { file: String },
+
+	/// Translate nyanlang code into another language
+	Translate {
+		file: String,
+
+		/// language to translate into
+		#[arg(long, value_enum)]
+		to: Target,
+	},
+}
+
+#[derive(Clone, ValueEnum)]
+enum Dump {
+	/// Rust debug representation
+	Debug,
+	/// JSON
+	#[cfg(feature = "serde")]
+	Json,
+	/// S-expressions, with the byte span of each node
+	Sexp,
+}
+
+#[derive(Clone, ValueEnum)]
+enum Target {
+	/// WebAssembly text format
+	Wat,
+}
+
+fn parse_range(range: &str) -> Result<(usize, usize), String> {
+	let (start, end) = range
+		.split_once(':')
+		.ok_or("expected START:END")?;
+	let line = |line: &str| match line.parse::<usize>() {
+		Ok(0) => Err(String::from("lines start at 1")),
+		Ok(line) => Ok(line),
+		Err(err) => Err(format!("invalid line `{line}`: {err}")),
+	};
+	let (start, end) = (line(start)?, line(end)?);
+
+	if start > end {
+		return Err(format!(
+			"START {start} is after END {end}"
+		));
+	}
+
+	Ok((start, end))
+}
+
+/// Reports the error on stderr and exits with code 1.
+fn fail(message: impl Display) -> ! {
+	eprintln!("{message}");
+	exit(1)
+}
+
+/// Reports why the code of the file can't be handled, along with where
+/// it failed if the error is tied to a token, and exits with code 1.
+fn fail_at(action: &str, file: &str, code: &str, err: Error) -> ! {
+	match err.span() {
+		Some(at) => fail(format!(
+			"Can't {action} {file}: {err} at {}",
+			describe(code, Some(at))
+		)),
+		None => fail(format!("Can't {action} {file}: {err}")),
+	}
+}
+
+fn read(file: &str) -> String {
+	read_to_string(file)
+		.unwrap_or_else(|err| fail(format!("Can't read {file}: {err}")))
+}
+
+fn read_stdin() -> String {
+	read_all(stdin())
+		.unwrap_or_else(|err| fail(format!("Can't read from stdin: {err}")))
+}
+
+fn save(file: &str, code: String) {
+	write(file, code)
+		.unwrap_or_else(|err| fail(format!("Can't write to {file}: {err}")))
+}
+
+fn config(file: &str) -> Config {
+	Config::discover(Path::new(file)).unwrap_or_else(|err| {
+		fail(format!("Can't load the configuration: {err}"))
+	})
+}
+
+fn describe(code: &str, span: Option<Span>) -> String {
+	let Some(span) = span else {
+		return String::from("end of code");
+	};
+	let (line, col) = span.line_col(code);
+
+	match &code[span.start..span.end] {
+		"" => format!("line {line}, column {col}"),
+		tok => format!("line {line}, column {col} (`{tok}`)"),
+	}
 }
 
 fn main() {
 	let opt = Arg::parse();
 
-	let code =
-		read_to_string(&opt.file).expect(&format!("Can't read {}", &opt.file));
-	let result = format(code);
+	match opt.command {
+		Some(Command::Ast { file, format }) => {
+			let format = match format {
+				Dump::Debug => AstFormat::Debug,
+				#[cfg(feature = "serde")]
+				Dump::Json => AstFormat::Json,
+				Dump::Sexp => AstFormat::Sexp,
+			};
+
+			let code = read(&file);
 
-	if opt.write {
-		write(&opt.file, result)
-			.expect(&format!("Can't write to {}", &opt.file));
-	} else {
-		println!("{result}");
+			match dump_ast(&code, format) {
+				Ok(tree) => print!("{tree}"),
+				Err(err) => fail_at("parse", &file, &code, err),
+			}
+		},
+		Some(Command::Generate { text }) => {
+			let text = text.unwrap_or_else(read_stdin);
+
+			print!("{}", generate_code(&text));
+		},
+		Some(Command::Lint {
+			file,
+			fix: true,
+			write,
+		}) => {
+			let code = read(&file);
+			let fixed = fix_code(&code, &config(&file))
+				.unwrap_or_else(|err| fail_at("fix", &file, &code, err));
+
+			for (verb, diagnostics) in
+				[("fixed", &fixed.applied), ("kept", &fixed.refused)]
+			{
+				for diagnostic in diagnostics {
+					let (line, col) = diagnostic.span.line_col(&code);
+
+					eprintln!(
+						"{file}:{line}:{col}: {verb}[{}]: {}",
+						diagnostic.rule.id(),
+						diagnostic.message
+					);
+				}
+			}
+			eprintln!(
+				"{file}: {} fixed, {} kept",
+				fixed.applied.len(),
+				fixed.refused.len()
+			);
+
+			if write {
+				save(&file, fixed.code);
+			} else {
+				print!("{}", fixed.code);
+			}
+		},
+		Some(Command::Lint { file, .. }) => {
+			let code = read(&file);
+			let diagnostics = lint_code(&code, &config(&file))
+				.unwrap_or_else(|err| fail_at("lint", &file, &code, err));
+
+			for diagnostic in &diagnostics {
+				let (line, col) = diagnostic.span.line_col(&code);
+
+				println!(
+					"{file}:{line}:{col}: {}[{}]: {}",
+					diagnostic.severity,
+					diagnostic.rule.id(),
+					diagnostic.message
+				);
+			}
+
+			if diagnostics
+				.iter()
+				.any(|d| d.severity == Severity::Error)
+			{
+				exit(1);
+			}
+		},
+		#[cfg(feature = "lsp")]
+		Some(Command::Lsp) => {
+			if let Err(err) = run_language_server() {
+				fail(format!("Language server failed: {err}"))
+			}
+		},
+		Some(Command::Run { file }) => {
+			let code = read(&file);
+
+			if let Err(err) = run_code(&code, stdin(), stdout()) {
+				fail_at("run", &file, &code, err)
+			}
+		},
+		Some(Command::Translate { file, to }) => {
+			let code = read(&file);
+			let result = match to {
+				Target::Wat => translate_to_wat(&code),
+			};
+
+			match result {
+				Ok(result) => print!("{result}"),
+				Err(err) => fail_at("translate", &file, &code, err),
+			}
+		},
+		#[cfg(feature = "serde")]
+		None if opt.from_json => {
+			let file = opt.file.unwrap();
+			let json = match file.as_str() {
+				"-" => read_stdin(),
+				_ => read(&file),
+			};
+
+			match format_ast_json(&json) {
+				Ok(code) => print!("{code}"),
+				Err(err) => fail_at("format", &file, &json, err),
+			}
+		},
+		None => {
+			let file = opt.file.unwrap();
+			let code = read(&file);
+			let config = config(&file);
+			let result = match opt.range {
+				Some((start, end)) => format_code_range(
+					&code,
+					Span::lines(&code, start, end),
+					&config,
+				)
+				.map(|edits| apply_edits(&code, &edits)),
+				None => Ok(format(code.clone())),
+			}
+			.unwrap_or_else(|err| fail_at("format", &file, &code, err));
+
+			if opt.verify {
+				if let Err(err) = verify_formatting(&code, &result, &config) {
+					eprintln!("{file}: {err}");
+					if let Error::Diverged { input, output } = err {
+						eprintln!("  input:  {}", describe(&code, input));
+						eprintln!(
+							"  output: {}",
+							describe(&result, output)
+						);
+					}
+					exit(1);
+				}
+			}
+
+			if opt.check_idempotency {
+				if let Err(err) = check_legacy_idempotency(&result) {
+					eprintln!("{file}: {err}");
+					if let Error::NotIdempotent { at } = err {
+						eprintln!(
+							"  output: {}",
+							describe(&result, Some(Span::new(at, at)))
+						);
+					}
+					exit(1);
+				}
+			}
+
+			if opt.write {
+				save(&file, result);
+			} else if opt.range.is_some() {
+				print!("{result}");
+			} else {
+				println!("{result}");
+			}
+		},
 	}
 }
+
+#[cfg(test)]
+#[path = "main.spec.rs"]
+mod tests;
@@ -0,0 +1,30 @@
+use super::*;
+use clap::error::ErrorKind;
+
+fn range(range: &str) -> Result<Option<(usize, usize)>, ErrorKind> {
+	Arg::try_parse_from(["nyanfmt", "a.nyan", "--range", range])
+		.map(|arg| arg.range)
+		.map_err(|err| err.kind())
+}
+
+#[test]
+fn range_must_be_parsed() {
+	assert_eq!(range("2:4"), Ok(Some((2, 4))));
+	assert_eq!(range("3:3"), Ok(Some((3, 3))));
+}
+
+#[test]
+fn range_must_not_start_at_line_zero() {
+	assert_eq!(
+		range("0:3"),
+		Err(ErrorKind::ValueValidation)
+	)
+}
+
+#[test]
+fn range_must_not_start_after_its_end() {
+	assert_eq!(
+		range("4:2"),
+		Err(ErrorKind::ValueValidation)
+	)
+}
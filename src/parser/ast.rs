@@ -1,17 +1,45 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Span};
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Root(pub Code);
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(Serialize, Deserialize),
+	serde(rename_all = "snake_case")
+)]
 pub enum HeadTok {
 	Inc,
 	Dec,
 	Debug,
 }
 
+impl HeadTok {
+	/// Name of the token in the serialized tree.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Inc => "inc",
+			Self::Dec => "dec",
+			Self::Debug => "debug",
+		}
+	}
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Head(pub Vec<HeadTok>);
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(Serialize, Deserialize),
+	serde(rename_all = "snake_case")
+)]
 pub enum BodyTok {
 	Out,
 	In,
@@ -19,37 +47,148 @@ pub enum BodyTok {
 	JumpLeft,
 }
 
+impl BodyTok {
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Out => "out",
+			Self::In => "in",
+			Self::JumpRight => "jump_right",
+			Self::JumpLeft => "jump_left",
+		}
+	}
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Body(pub Vec<BodyTok>);
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(Serialize, Deserialize),
+	serde(rename_all = "snake_case")
+)]
 pub enum TailTok {
 	Right,
 	Left,
 }
 
+impl TailTok {
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Right => "right",
+			Self::Left => "left",
+		}
+	}
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Tail(pub Vec<TailTok>);
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(Serialize, Deserialize),
+	serde(deny_unknown_fields)
+)]
 pub struct Word {
 	pub head: Option<Head>,
 	pub body: Option<Body>,
 	pub tail: Option<Tail>,
+	/// comments following the word on its line, before the next word or
+	/// the line break
+	#[cfg_attr(
+		feature = "serde",
+		serde(default, skip_serializing_if = "Vec::is_empty")
+	)]
+	pub comments: Vec<Comment>,
+	/// code skipped by the recovering parser after the word and its
+	/// comments, alone in a word of its own if nothing comes before it on
+	/// its line
+	#[cfg_attr(
+		feature = "serde",
+		serde(skip_deserializing, skip_serializing_if = "Option::is_none")
+	)]
+	pub invalid: Option<Invalid>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Sentence(pub Vec<Word>);
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Comment(pub String);
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(Serialize, Deserialize),
+	serde(from = "ParagraphFields", into = "ParagraphFields")
+)]
 pub struct Paragraph(pub Vec<Comment>, pub Vec<Sentence>);
 
+/// Fields of a [`Paragraph`] by name, as it is serialized.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParagraphFields {
+	comments: Vec<Comment>,
+	sentences: Vec<Sentence>,
+}
+
+#[cfg(feature = "serde")]
+impl From<ParagraphFields> for Paragraph {
+	fn from(fields: ParagraphFields) -> Self {
+		Self(fields.comments, fields.sentences)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl From<Paragraph> for ParagraphFields {
+	fn from(Paragraph(comments, sentences): Paragraph) -> Self {
+		Self {
+			comments,
+			sentences,
+		}
+	}
+}
+
+/// Code skipped by the recovering parser, from what it could not parse to
+/// the next line break or comment.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Invalid {
+	pub span: Span,
+	/// the skipped code, as it is in the source
+	pub text: String,
+	/// why the code could not be parsed, at the first token skipped
+	#[cfg_attr(feature = "serde", serde(serialize_with = "message"))]
+	pub error: Error,
+}
+
+/// Serializes the error as its message.
+#[cfg(feature = "serde")]
+fn message<S: serde::Serializer>(
+	error: &Error,
+	serializer: S,
+) -> Result<S::Ok, S::Error> {
+	serializer.collect_str(error)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(Serialize, Deserialize),
+	serde(default, deny_unknown_fields)
+)]
 pub struct Code {
 	pub leading_sentences: Vec<Sentence>,
 	pub paragraphs: Vec<Paragraph>,
 	pub trailing_comments: Vec<Comment>,
 }
+
+#[cfg(all(test, feature = "serde"))]
+#[path = "ast.spec.rs"]
+mod tests;
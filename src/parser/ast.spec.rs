@@ -0,0 +1,109 @@
+use super::*;
+use crate::{parse_code, parse_code_recovering};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[test]
+fn tree_must_serialize_to_json() {
+	assert_eq!(
+		serde_json::to_value(parse_code("냥? \"주석\"\n~.-!").unwrap())
+			.unwrap(),
+		json!({
+			"leading_sentences": [
+				[{
+					"head": ["inc"],
+					"body": null,
+					"tail": ["right"],
+					"comments": ["주석"],
+				}],
+				[{
+					"head": null,
+					"body": ["jump_right", "out", "jump_left"],
+					"tail": ["left"],
+				}],
+			],
+			"paragraphs": [],
+			"trailing_comments": [],
+		})
+	)
+}
+
+#[test]
+fn skipped_code_must_serialize_with_its_word() {
+	assert_eq!(
+		serde_json::to_value(parse_code_recovering("냥 @")).unwrap(),
+		json!({
+			"leading_sentences": [
+				[{
+					"head": ["inc"],
+					"body": null,
+					"tail": null,
+					"invalid": {
+						"span": { "start": 4, "end": 5 },
+						"text": "@",
+						"error": "failed to tokenize the code",
+					},
+				}],
+			],
+			"paragraphs": [],
+			"trailing_comments": [],
+		})
+	)
+}
+
+#[test]
+fn tree_must_deserialize_from_its_json() {
+	let root = parse_code("냥뀨? ~.-\n\"주석\"\n냥!\n\n\"끝\"").unwrap();
+	let json = serde_json::to_string(&root).unwrap();
+
+	assert_eq!(
+		serde_json::from_str::<Root>(&json).unwrap(),
+		root
+	)
+}
+
+#[test]
+fn missing_lists_must_default_to_empty() {
+	assert_eq!(
+		serde_json::from_value::<Root>(json!({
+			"leading_sentences": [[{ "head": ["inc"] }]],
+		}))
+		.unwrap(),
+		Root(Code {
+			leading_sentences: vec![Sentence(vec![Word {
+				head: Some(Head(vec![HeadTok::Inc])),
+				body: None,
+				tail: None,
+				comments: vec![],
+				invalid: None,
+			}])],
+			..Code::default()
+		})
+	)
+}
+
+#[test]
+fn unknown_fields_must_be_rejected() {
+	assert!(serde_json::from_value::<Root>(json!({
+		"leading_sentences": [[{ "head": ["inc"], "neck": ["out"] }]],
+	}))
+	.is_err())
+}
+
+#[test]
+fn json_tree_must_be_formatted() {
+	let json = json!({
+		"leading_sentences": [
+			[
+				{ "head": ["inc", "inc"] },
+				{ "body": ["jump_right", "out"], "tail": ["right"] },
+			],
+		],
+		"trailing_comments": ["끝"],
+	});
+
+	assert_eq!(
+		crate::format_ast_json(&json.to_string()),
+		Ok(String::from("냥냥 ~.?\n\n\"끝\"\n"))
+	)
+}
@@ -0,0 +1,239 @@
+//! Builders assembling syntax trees in code, to be formatted through the
+//! `Display` implementation of [`Root`].
+//!
+//! ```
+//! use nyanfmt::ast::{Root, Word};
+//!
+//! let root = Root::builder()
+//!     .comment("hello")
+//!     .sentence([
+//!         Word::builder().inc(3).out().right(2).build()?,
+//!         Word::builder().dec(1).build()?,
+//!     ])
+//!     .build()?;
+//!
+//! assert_eq!(root.to_string(), "\"hello\"\n냥냥냥.?? 냐\n");
+//! # Ok::<(), nyanfmt::Error>(())
+//! ```
+
+use std::iter;
+
+use super::{ast::*, validate};
+use crate::Error;
+
+impl Word {
+	pub fn builder() -> WordBuilder {
+		WordBuilder::default()
+	}
+}
+
+impl Root {
+	pub fn builder() -> RootBuilder {
+		RootBuilder::default()
+	}
+}
+
+/// Builds a [`Word`] token by token, checking that the tokens of its head
+/// come before those of its body, those before the tokens of its tail, and
+/// all of them before its comments.
+#[derive(Clone, Debug, Default)]
+pub struct WordBuilder {
+	head: Vec<HeadTok>,
+	body: Vec<BodyTok>,
+	tail: Vec<TailTok>,
+	comments: Vec<Comment>,
+	/// the first token added out of order
+	misplaced: Option<String>,
+}
+
+impl WordBuilder {
+	fn misplace(&mut self, tok: &str, after: &str) {
+		self.misplaced.get_or_insert_with(|| {
+			format!("`{tok}` comes after the {after} of the word")
+		});
+	}
+
+	pub fn head(mut self, tok: HeadTok) -> Self {
+		if !self.comments.is_empty() {
+			self.misplace(tok.name(), "comments");
+		} else if !self.tail.is_empty() {
+			self.misplace(tok.name(), "tail");
+		} else if !self.body.is_empty() {
+			self.misplace(tok.name(), "body");
+		} else {
+			self.head.push(tok);
+		}
+		self
+	}
+
+	pub fn body(mut self, tok: BodyTok) -> Self {
+		if !self.comments.is_empty() {
+			self.misplace(tok.name(), "comments");
+		} else if !self.tail.is_empty() {
+			self.misplace(tok.name(), "tail");
+		} else {
+			self.body.push(tok);
+		}
+		self
+	}
+
+	pub fn tail(mut self, tok: TailTok) -> Self {
+		if !self.comments.is_empty() {
+			self.misplace(tok.name(), "comments");
+		} else {
+			self.tail.push(tok);
+		}
+		self
+	}
+
+	/// Adds a comment after the tokens of the word, on its line.
+	pub fn comment(mut self, comment: impl Into<String>) -> Self {
+		self.comments
+			.push(Comment(comment.into()));
+		self
+	}
+
+	fn repeat<T: Clone>(
+		self,
+		add: fn(Self, T) -> Self,
+		tok: T,
+		count: usize,
+	) -> Self {
+		iter::repeat_n(tok, count).fold(self, add)
+	}
+
+	pub fn inc(self, count: usize) -> Self {
+		self.repeat(Self::head, HeadTok::Inc, count)
+	}
+
+	pub fn dec(self, count: usize) -> Self {
+		self.repeat(Self::head, HeadTok::Dec, count)
+	}
+
+	pub fn debug(self) -> Self {
+		self.head(HeadTok::Debug)
+	}
+
+	pub fn out(self) -> Self {
+		self.body(BodyTok::Out)
+	}
+
+	/// Adds [`BodyTok::In`], reading a byte of input.
+	pub fn input(self) -> Self {
+		self.body(BodyTok::In)
+	}
+
+	pub fn jump_right(self) -> Self {
+		self.body(BodyTok::JumpRight)
+	}
+
+	pub fn jump_left(self) -> Self {
+		self.body(BodyTok::JumpLeft)
+	}
+
+	pub fn right(self, count: usize) -> Self {
+		self.repeat(Self::tail, TailTok::Right, count)
+	}
+
+	pub fn left(self, count: usize) -> Self {
+		self.repeat(Self::tail, TailTok::Left, count)
+	}
+
+	/// Finishes the word, failing if a token was added out of order or if
+	/// no token was added at all. Comments are not checked, as any text is
+	/// escaped into a valid comment when formatted.
+	pub fn build(self) -> Result<Word, Error> {
+		if let Some(misplaced) = self.misplaced {
+			return Err(Error::InvalidAst(misplaced));
+		}
+		if self.head.is_empty() && self.body.is_empty() && self.tail.is_empty()
+		{
+			return Err(Error::InvalidAst(String::from(
+				"word has no head, body or tail",
+			)));
+		}
+
+		Ok(Word {
+			head: (!self.head.is_empty()).then_some(Head(self.head)),
+			body: (!self.body.is_empty()).then_some(Body(self.body)),
+			tail: (!self.tail.is_empty()).then_some(Tail(self.tail)),
+			comments: self.comments,
+			invalid: None,
+		})
+	}
+}
+
+/// Builds a [`Root`] from its sentences and comments, in the order they
+/// appear in the code.
+///
+/// Like in parsed code, the sentences before the first comment lead the
+/// code, comments followed by sentences start a paragraph, and the
+/// comments after the last sentence trail the code.
+#[derive(Clone, Debug, Default)]
+pub struct RootBuilder {
+	code: Code,
+}
+
+impl RootBuilder {
+	pub fn sentence(mut self, words: impl IntoIterator<Item = Word>) -> Self {
+		let sentence = Sentence(words.into_iter().collect());
+		let code = &mut self.code;
+
+		if !code.trailing_comments.is_empty() {
+			code.paragraphs.push(Paragraph(
+				std::mem::take(&mut code.trailing_comments),
+				vec![sentence],
+			));
+		} else if let Some(Paragraph(_, sentences)) = code.paragraphs.last_mut()
+		{
+			sentences.push(sentence);
+		} else {
+			code.leading_sentences.push(sentence);
+		}
+		self
+	}
+
+	pub fn comment(mut self, comment: impl Into<String>) -> Self {
+		self.code
+			.trailing_comments
+			.push(Comment(comment.into()));
+		self
+	}
+
+	/// Adds code skipped by the recovering parser after the last word, if
+	/// it is on the same line, or else as a sentence of its own.
+	pub(crate) fn invalid(mut self, invalid: Invalid, same_line: bool) -> Self {
+		let code = &mut self.code;
+		let last = match code.paragraphs.last_mut() {
+			_ if !code.trailing_comments.is_empty() => None,
+			Some(Paragraph(_, sentences)) => sentences.last_mut(),
+			None => code.leading_sentences.last_mut(),
+		};
+
+		match last.and_then(|Sentence(words)| words.last_mut()) {
+			Some(word) if same_line => {
+				word.invalid = Some(invalid);
+				self
+			},
+			_ => self.sentence([Word {
+				head: None,
+				body: None,
+				tail: None,
+				comments: vec![],
+				invalid: Some(invalid),
+			}]),
+		}
+	}
+
+	/// Finishes the tree, failing if a sentence has no words.
+	pub fn build(self) -> Result<Root, Error> {
+		let root = Root(self.code);
+		validate(&root)?;
+
+		Ok(root)
+	}
+}
+
+#[cfg(test)]
+#[path = "builder.spec.rs"]
+mod tests;
@@ -0,0 +1,155 @@
+use super::*;
+use crate::parse_code;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn word_must_keep_tokens_in_order() {
+	assert_eq!(
+		Word::builder()
+			.inc(3)
+			.debug()
+			.out()
+			.input()
+			.right(2)
+			.left(1)
+			.build(),
+		Ok(word!(
+			[HeadTok::Inc, HeadTok::Inc, HeadTok::Inc, HeadTok::Debug],
+			[BodyTok::Out, BodyTok::In],
+			[TailTok::Right, TailTok::Right, TailTok::Left],
+		))
+	)
+}
+
+#[test]
+fn missing_parts_must_be_none() {
+	assert_eq!(
+		Word::builder().jump_right().build(),
+		Ok(word!(, [BodyTok::JumpRight],))
+	)
+}
+
+#[test]
+fn head_after_body_must_be_rejected() {
+	assert_eq!(
+		Word::builder().out().inc(1).build(),
+		Err(Error::InvalidAst(String::from(
+			"`inc` comes after the body of the word"
+		)))
+	)
+}
+
+#[test]
+fn first_misplaced_token_must_be_reported() {
+	assert_eq!(
+		Word::builder()
+			.left(1)
+			.jump_left()
+			.dec(1)
+			.build(),
+		Err(Error::InvalidAst(String::from(
+			"`jump_left` comes after the tail of the word"
+		)))
+	)
+}
+
+#[test]
+fn comments_must_come_after_the_tokens() {
+	assert_eq!(
+		Word::builder()
+			.inc(1)
+			.comment("a")
+			.comment("b")
+			.build(),
+		Ok(word!([HeadTok::Inc],,; "a", "b"))
+	)
+}
+
+#[test]
+fn token_after_comment_must_be_rejected() {
+	assert_eq!(
+		Word::builder()
+			.out()
+			.comment("a")
+			.right(1)
+			.build(),
+		Err(Error::InvalidAst(String::from(
+			"`right` comes after the comments of the word"
+		)))
+	)
+}
+
+#[test]
+fn empty_word_must_be_rejected() {
+	assert_eq!(
+		Word::builder().inc(0).build(),
+		Err(Error::InvalidAst(String::from(
+			"word has no head, body or tail"
+		)))
+	)
+}
+
+#[test]
+fn root_must_match_parsed_code() {
+	let inc = || Word::builder().inc(1).build().unwrap();
+	let right = Word::builder()
+		.right(1)
+		.build()
+		.unwrap();
+
+	assert_eq!(
+		Root::builder()
+			.sentence([inc()])
+			.comment("a")
+			.comment("b")
+			.sentence([right, inc()])
+			.sentence([inc()])
+			.comment("c")
+			.sentence([inc()])
+			.comment("d")
+			.build(),
+		parse_code("냥\n\"a\"\n\"b\"\n? 냥\n냥\n\"c\"\n냥\n\"d\"")
+	)
+}
+
+#[test]
+fn built_root_must_format() {
+	assert_eq!(
+		Root::builder()
+			.comment("hello")
+			.sentence([
+				Word::builder()
+					.inc(3)
+					.out()
+					.right(2)
+					.build()
+					.unwrap(),
+				Word::builder().dec(1).build().unwrap(),
+			])
+			.build()
+			.unwrap()
+			.to_string(),
+		"\"hello\"\n냥냥냥.?? 냐\n"
+	)
+}
+
+#[test]
+fn empty_sentence_must_be_rejected() {
+	assert_eq!(
+		Root::builder().sentence([]).build(),
+		Err(Error::InvalidAst(String::from(
+			"leading_sentences[0]: sentence has no words"
+		)))
+	)
+}
+
+#[test]
+fn quote_in_comment_must_be_escaped() {
+	assert_eq!(
+		Root::builder()
+			.comment("a\"b")
+			.build()
+			.map(|root| root.to_string()),
+		Ok(String::from("\"a\\\"b\"\n"))
+	)
+}
@@ -0,0 +1,458 @@
+use std::ops::Range;
+
+use super::ast::*;
+use crate::{lexer::Span, CommentPlacement, FormatConfig};
+
+/// Comment turning formatting off until the next [`ON`] comment, or the
+/// end of the code. Following a word, it turns it off from its own line,
+/// and [`ON`] turns it back on after its own line.
+const OFF: &str = "nyanfmt: off";
+const ON: &str = "nyanfmt: on";
+/// Comment keeping the layout of the next sentence, or of its own line
+/// when it follows a word.
+const SKIP: &str = "nyanfmt: skip";
+
+fn is(Comment(comment): &Comment, directive: &str) -> bool {
+	comment.trim() == directive
+}
+
+#[derive(Clone, Copy)]
+enum Piece<'a> {
+	Comment(&'a Comment),
+	Sentence(&'a Sentence),
+}
+
+impl<'a> Piece<'a> {
+	/// Comments of the line in order, where directives are looked for.
+	/// Directives following a word on its line apply to the whole line.
+	fn comments(self) -> Vec<&'a Comment> {
+		match self {
+			Piece::Comment(comment) => vec![comment],
+			Piece::Sentence(Sentence(words)) => words
+				.iter()
+				.flat_map(|word| &word.comments)
+				.collect(),
+		}
+	}
+
+	fn has(self, directive: &str) -> bool {
+		self.comments()
+			.into_iter()
+			.any(|comment| is(comment, directive))
+	}
+}
+
+/// A line of the formatted code, covering `items` in the order of the
+/// tokens that are not `NewLine`.
+struct Entry<'a> {
+	piece: Piece<'a>,
+	items: Range<usize>,
+	/// whether a blank line separates it from the previous entry
+	blank: bool,
+}
+
+fn len(Sentence(words): &Sentence) -> usize {
+	words
+		.iter()
+		.map(|word| {
+			word.head
+				.as_ref()
+				.map_or(0, |Head(v)| v.len())
+				+ word
+					.body
+					.as_ref()
+					.map_or(0, |Body(v)| v.len())
+				+ word
+					.tail
+					.as_ref()
+					.map_or(0, |Tail(v)| v.len())
+				+ word.comments.len()
+		})
+		.sum()
+}
+
+fn entries(Root(code): &Root) -> Vec<Entry<'_>> {
+	let mut entries: Vec<Entry> = vec![];
+	let mut push = |piece, first| {
+		let start = entries
+			.last()
+			.map_or(0, |entry| entry.items.end);
+		let len = match piece {
+			Piece::Comment(_) => 1,
+			Piece::Sentence(sentence) => len(sentence),
+		};
+
+		entries.push(Entry {
+			piece,
+			items: start..start + len,
+			blank: first && start > 0,
+		});
+	};
+
+	for sentence in &code.leading_sentences {
+		push(Piece::Sentence(sentence), false);
+	}
+	for Paragraph(comments, sentences) in &code.paragraphs {
+		let pieces = comments
+			.iter()
+			.map(Piece::Comment)
+			.chain(sentences.iter().map(Piece::Sentence));
+
+		for (i, piece) in pieces.enumerate() {
+			push(piece, i == 0);
+		}
+	}
+	for (i, comment) in code
+		.trailing_comments
+		.iter()
+		.enumerate()
+	{
+		push(Piece::Comment(comment), i == 0);
+	}
+
+	entries
+}
+
+enum Chunk<'a> {
+	Formatted {
+		piece: Piece<'a>,
+		items: Range<usize>,
+	},
+	/// items kept as they are in the source, up to the end of the source
+	/// if `to_end` is set, which end with a sentence if `sentence` is set
+	Verbatim {
+		items: Range<usize>,
+		to_end: bool,
+		sentence: bool,
+	},
+}
+
+/// Splits the code into lines to format and regions to keep as they are,
+/// each along with whether a blank line comes before it.
+fn chunks(root: &Root) -> Vec<(bool, Chunk<'_>)> {
+	let entries = entries(root);
+	let mut chunks = vec![];
+	let mut skip = false;
+	let mut i = 0;
+
+	while i < entries.len() {
+		let entry = &entries[i];
+
+		let chunk = match entry.piece {
+			piece if piece.has(OFF) => {
+				let comments = piece.comments();
+				let off = comments
+					.iter()
+					.position(|comment| is(comment, OFF))
+					.expect("the line has an off directive");
+				let on = if comments[off..]
+					.iter()
+					.any(|comment| is(comment, ON))
+				{
+					Some(i)
+				} else {
+					entries[i + 1..]
+						.iter()
+						.position(|entry| entry.piece.has(ON))
+						.map(|j| i + 1 + j)
+				};
+
+				match on {
+					Some(on) => {
+						i = on;
+						Chunk::Verbatim {
+							items: entry.items.start..entries[on].items.end,
+							to_end: false,
+							sentence: matches!(
+								entries[on].piece,
+								Piece::Sentence(_)
+							),
+						}
+					},
+					None => {
+						i = entries.len();
+						Chunk::Verbatim {
+							items: entry.items.start..entries[i - 1].items.end,
+							to_end: true,
+							sentence: matches!(
+								entries[i - 1].piece,
+								Piece::Sentence(_)
+							),
+						}
+					},
+				}
+			},
+			Piece::Comment(comment) => {
+				skip |= is(comment, SKIP);
+				Chunk::Formatted {
+					piece: entry.piece,
+					items: entry.items.clone(),
+				}
+			},
+			piece @ Piece::Sentence(_) if skip || piece.has(SKIP) => {
+				skip = false;
+				Chunk::Verbatim {
+					items: entry.items.clone(),
+					to_end: false,
+					sentence: true,
+				}
+			},
+			Piece::Sentence(_) => Chunk::Formatted {
+				piece: entry.piece,
+				items: entry.items.clone(),
+			},
+		};
+
+		chunks.push((entry.blank, chunk));
+		i += 1;
+	}
+
+	chunks
+}
+
+fn comment(Comment(comment): &Comment, config: &FormatConfig) -> String {
+	Comment(config.comment_whitespace.apply(comment)).to_string()
+}
+
+/// Position of the first and the last part of the word, counting the
+/// head, the body and the tail in this order.
+fn parts(word: &Word) -> (usize, usize) {
+	let present = [
+		word.head.is_some(),
+		word.body.is_some(),
+		word.tail.is_some(),
+	];
+
+	(
+		present
+			.iter()
+			.position(|&p| p)
+			.unwrap_or(0),
+		present
+			.iter()
+			.rposition(|&p| p)
+			.unwrap_or(0),
+	)
+}
+
+/// Joins the words that would parse as one once the comments between them
+/// are gone, such as `냥` and `냥?`.
+fn merge(words: impl Iterator<Item = Word>) -> Vec<Word> {
+	let mut merged: Vec<Word> = vec![];
+
+	for word in words {
+		match merged.last_mut() {
+			Some(last) if parts(last).1 <= parts(&word).0 => {
+				if let Some(Head(toks)) = word.head {
+					last.head
+						.get_or_insert(Head(vec![]))
+						.0
+						.extend(toks);
+				}
+				if let Some(Body(toks)) = word.body {
+					last.body
+						.get_or_insert(Body(vec![]))
+						.0
+						.extend(toks);
+				}
+				if let Some(Tail(toks)) = word.tail {
+					last.tail
+						.get_or_insert(Tail(vec![]))
+						.0
+						.extend(toks);
+				}
+			},
+			_ => merged.push(word),
+		}
+	}
+
+	merged
+}
+
+/// Formats the sentence, with the comments of its words after them or on
+/// the lines above it, separated by `line_break`.
+fn sentence(
+	Sentence(words): &Sentence,
+	config: &FormatConfig,
+	line_break: &str,
+) -> String {
+	let bare = |word: &Word| Word {
+		comments: vec![],
+		..word.clone()
+	};
+
+	match config.comment_placement {
+		CommentPlacement::Inline => words
+			.iter()
+			.map(|word| {
+				word.comments
+					.iter()
+					.fold(bare(word).to_string(), |word, c| {
+						word + " " + &comment(c, config)
+					})
+			})
+			.collect::<Vec<_>>()
+			.join(" "),
+		CommentPlacement::Above => words
+			.iter()
+			.flat_map(|word| &word.comments)
+			.map(|c| comment(c, config))
+			.chain([Sentence(merge(words.iter().map(bare))).to_string()])
+			.collect::<Vec<_>>()
+			.join(line_break),
+	}
+}
+
+/// Part of the code, formatted.
+pub struct Block {
+	/// where the block is in the source
+	pub span: Span,
+	pub text: String,
+	/// whether the block is kept as it is up to the end of the source
+	pub to_end: bool,
+}
+
+/// Formats the lines of the code one by one, but keeps the regions marked
+/// by directive comments exactly as they are in `source`, each along with
+/// whether a blank line comes before it.
+///
+/// `spans` holds the span in `source` of every token that is not a
+/// `NewLine`. The line breaks put between lines follow the `new-line`
+/// setting, while those inside comments and kept regions stay as written.
+///
+/// Comments moved above a sentence that follows another sentence would
+/// start a paragraph once the code is parsed again, so a blank line comes
+/// before them.
+pub fn lines(
+	root: &Root,
+	spans: &[Span],
+	source: &str,
+	config: &FormatConfig,
+) -> Vec<(bool, Block)> {
+	let line_break = config.new_line.resolve(source, spans);
+	let mut lines = vec![];
+	let mut after_sentence = false;
+
+	for (blank, chunk) in chunks(root) {
+		let ends_with_sentence = match chunk {
+			Chunk::Formatted { piece, .. } => {
+				matches!(piece, Piece::Sentence(_))
+			},
+			Chunk::Verbatim { sentence, .. } => sentence,
+		};
+		let (span, text, to_end) = match chunk {
+			Chunk::Formatted { piece, items } => (
+				Span::new(
+					spans[items.start].start,
+					spans[items.end - 1].end,
+				),
+				match piece {
+					Piece::Comment(c) => comment(c, config),
+					Piece::Sentence(s) => {
+						let text = sentence(s, config, line_break);
+						let moved = config.comment_placement
+							== CommentPlacement::Above
+							&& s.0
+								.iter()
+								.any(|word| !word.comments.is_empty());
+
+						if moved && after_sentence && !blank {
+							format!("{line_break}{text}")
+						} else {
+							text
+						}
+					},
+				},
+				false,
+			),
+			Chunk::Verbatim { items, to_end, .. } => {
+				let span = Span::new(
+					spans[items.start].start,
+					if to_end {
+						source.len()
+					} else {
+						spans[items.end - 1].end
+					},
+				);
+
+				(
+					span,
+					source[span.start..span.end].to_string(),
+					to_end,
+				)
+			},
+		};
+
+		after_sentence = ends_with_sentence;
+		lines.push((blank, Block { span, text, to_end }));
+	}
+
+	lines
+}
+
+/// Splits the code into blocks of lines not separated by a blank line,
+/// formatting them like [`lines`] does.
+pub fn blocks(
+	root: &Root,
+	spans: &[Span],
+	source: &str,
+	config: &FormatConfig,
+) -> Vec<Block> {
+	let line_break = config.new_line.resolve(source, spans);
+	let mut blocks: Vec<Block> = vec![];
+
+	for (blank, line) in lines(root, spans, source, config) {
+		match blocks.last_mut() {
+			Some(block) if !blank => {
+				block.span.end = line.span.end;
+				block.text.push_str(line_break);
+				block.text.push_str(&line.text);
+				block.to_end = line.to_end;
+			},
+			_ => blocks.push(line),
+		}
+	}
+
+	blocks
+}
+
+/// Formats the code like its `Display` implementation does, but keeps the
+/// regions marked by directive comments exactly as they are in `source`.
+pub fn format_with_source(
+	root: &Root,
+	spans: &[Span],
+	source: &str,
+	config: &FormatConfig,
+) -> String {
+	let line_break = config.new_line.resolve(source, spans);
+	let blocks = blocks(root, spans, source, config);
+	let mut res = blocks
+		.iter()
+		.map(|block| block.text.as_str())
+		.collect::<Vec<_>>()
+		.join(&line_break.repeat(2));
+
+	if blocks
+		.last()
+		.is_some_and(|block| !block.to_end)
+	{
+		res.push_str(line_break);
+	}
+
+	res
+}
+
+/// Items kept as they are in the source by directive comments.
+pub fn verbatim_items(root: &Root) -> Vec<Range<usize>> {
+	chunks(root)
+		.into_iter()
+		.filter_map(|(_, chunk)| match chunk {
+			Chunk::Verbatim { items, .. } => Some(items),
+			Chunk::Formatted { .. } => None,
+		})
+		.collect()
+}
+
+#[cfg(test)]
+#[path = "directive.spec.rs"]
+mod tests;
@@ -0,0 +1,258 @@
+use crate::{format_code, parse_and_format_code, Config};
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn code_without_directives_must_be_formatted_as_usual() {
+	for code in [
+		include_str!("../../samples/hello.nyan"),
+		include_str!("../../samples/sierpinski.nyan"),
+		"냥   ?\n\n\"a\"\n\"b\"\n냐 .\n\n\"c\"",
+		"\"a\"",
+		"",
+	] {
+		assert_eq!(
+			parse_and_format_code(code),
+			Ok(crate::parse_code(code)
+				.unwrap()
+				.to_string())
+		)
+	}
+}
+
+#[test]
+fn region_between_off_and_on_must_be_kept() {
+	let code = indoc! {r#"
+		냥  냥 ?
+
+		"nyanfmt: off"
+		 냥 냥 냥
+		   ? ? ?
+
+		"nyanfmt: on"
+		냐   .
+	"#};
+
+	assert_eq!(
+		parse_and_format_code(code),
+		Ok(String::from(indoc! {r#"
+			냥냥?
+
+			"nyanfmt: off"
+			 냥 냥 냥
+			   ? ? ?
+
+			"nyanfmt: on"
+			냐.
+		"#}))
+	)
+}
+
+#[test]
+fn region_after_off_without_on_must_be_kept_to_the_end() {
+	let code = "냥  ?\n\n\"nyanfmt: off\"\n냥 냥\n\n\"끝\"  \n";
+
+	assert_eq!(
+		parse_and_format_code(code),
+		Ok(String::from(
+			"냥?\n\n\"nyanfmt: off\"\n냥 냥\n\n\"끝\"  \n"
+		))
+	)
+}
+
+#[test]
+fn skip_must_keep_only_the_next_sentence() {
+	let code = indoc! {r#"
+		"nyanfmt: skip"
+		"주석"
+		냥 냥 ?
+		냥 냥 ?
+	"#};
+
+	assert_eq!(
+		parse_and_format_code(code),
+		Ok(String::from(indoc! {r#"
+			"nyanfmt: skip"
+			"주석"
+			냥 냥 ?
+			냥냥?
+		"#}))
+	)
+}
+
+#[test]
+fn kept_regions_must_stay_the_same_when_formatted_again() {
+	let code = "\"nyanfmt: off\"\n냥 ?\n\"nyanfmt: on\"\n냥 ?\n";
+	let formatted = parse_and_format_code(code).unwrap();
+
+	assert_eq!(
+		parse_and_format_code(&formatted),
+		Ok(formatted)
+	)
+}
+
+#[test]
+fn directives_following_a_word_must_apply_to_their_line() {
+	let code = indoc! {r#"
+		냥  냥 "nyanfmt: off"
+		 냥 냥
+		냐  냐 "nyanfmt: on"
+		냥  ?
+		냐  . "nyanfmt: skip"
+		냐  .
+	"#};
+
+	assert_eq!(
+		parse_and_format_code(code),
+		Ok(String::from(indoc! {r#"
+			냥  냥 "nyanfmt: off"
+			 냥 냥
+			냐  냐 "nyanfmt: on"
+			냥?
+			냐  . "nyanfmt: skip"
+			냐.
+		"#}))
+	)
+}
+
+#[test]
+fn off_and_on_on_the_same_line_must_keep_only_that_line() {
+	let code = "냥  냥 \"nyanfmt: off\" ? \"nyanfmt: on\"\n냐  .\n";
+
+	assert_eq!(
+		parse_and_format_code(code),
+		Ok(String::from(
+			"냥  냥 \"nyanfmt: off\" ? \"nyanfmt: on\"\n냐.\n"
+		))
+	)
+}
+
+#[test]
+fn inline_directives_must_stay_inline_when_comments_move_above() {
+	let code = "냥  냥 \"nyanfmt: off\"\n 냥\n\"nyanfmt: on\"\n냐 \"a\"\n";
+	let formatted = format_comments(code, "comment-placement = \"above\"");
+
+	assert_eq!(
+		(
+			formatted.as_str(),
+			format_comments(&formatted, "comment-placement = \"above\""),
+		),
+		(
+			"냥  냥 \"nyanfmt: off\"\n 냥\n\"nyanfmt: on\"\n\"a\"\n냐\n",
+			formatted.clone(),
+		)
+	)
+}
+
+fn format_comments(code: &str, options: &str) -> String {
+	format_code(
+		code,
+		&Config::from_toml(&format!("[format]\n{options}")).unwrap(),
+	)
+	.unwrap()
+}
+
+#[test]
+fn comments_must_stay_inline_by_default() {
+	assert_eq!(
+		format_comments("냥  ? \"a\"\n냐 \"b\" .", ""),
+		"냥? \"a\"\n냐 \"b\" .\n"
+	)
+}
+
+#[test]
+fn comments_moved_above_must_start_a_paragraph_after_a_sentence() {
+	assert_eq!(
+		format_comments(
+			"냥?\n냐 \"a\" . \"b\"\n\n\"c\"\n냥 \"d\"",
+			"comment-placement = \"above\"",
+		),
+		indoc! {r#"
+			냥?
+
+			"a"
+			"b"
+			냐.
+
+			"c"
+			"d"
+			냥
+		"#}
+	)
+}
+
+#[test]
+fn comment_whitespace_must_apply_to_every_comment() {
+	assert_eq!(
+		format_comments(
+			"\"  a   b \"\n냥 \" c\td \"",
+			"comment-whitespace = \"normalize\"",
+		),
+		"\"a b\"\n냥 \"c d\"\n"
+	)
+}
+
+#[test]
+fn directives_must_be_trimmed_before_whitespace_options() {
+	assert_eq!(
+		format_comments(
+			"\" nyanfmt: skip \"\n냥 냥",
+			"comment-whitespace = \"trim\"",
+		),
+		"\"nyanfmt: skip\"\n냥 냥\n"
+	)
+}
+
+#[test]
+fn multi_line_comments_must_keep_their_lines() {
+	let code = "\"  첫 줄  \n   둘째   줄\n\"\n냥 \"a\n b\" 냥";
+
+	assert_eq!(
+		[
+			format_comments(code, ""),
+			format_comments(
+				code,
+				"comment-whitespace = \"normalize\""
+			),
+		],
+		[
+			"\"  첫 줄  \n   둘째   줄\n\"\n냥 \"a\n b\" 냥\n",
+			"\"첫 줄\n둘째 줄\"\n냥 \"a\nb\" 냥\n",
+		]
+	)
+}
+
+#[test]
+fn quotes_in_comments_must_stay_escaped() {
+	assert_eq!(
+		format_comments(r#"냥  "say \"hi\" \\"  냥"#, ""),
+		"냥 \"say \\\"hi\\\" \\\\\" 냥\n"
+	)
+}
+
+#[test]
+fn crlf_must_only_replace_line_breaks_between_lines() {
+	let code = indoc! {r#"
+		"첫 줄
+		둘째 줄"
+		냥 냥 "a
+		b" ?
+
+		"nyanfmt: off"
+		냥
+		  냐
+		"nyanfmt: on"
+		냐   .
+	"#};
+
+	assert_eq!(
+		format_comments(code, "new-line = \"crlf\""),
+		concat!(
+			"\"첫 줄\n둘째 줄\"\r\n",
+			"냥냥 \"a\nb\" ?\r\n",
+			"\r\n",
+			"\"nyanfmt: off\"\n냥\n  냐\n\"nyanfmt: on\"\r\n",
+			"냐.\r\n",
+		)
+	)
+}
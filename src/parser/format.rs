@@ -1,9 +1,7 @@
-use std::{
-	fmt::{self, Display, Formatter},
-	ops::Not,
-};
+use std::fmt::{self, Display, Formatter};
 
 use super::ast::*;
+use crate::lexer::escape;
 
 impl Display for HeadTok {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -91,9 +89,8 @@ impl Display for Tail {
 
 impl Display for Word {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-		write!(
-			f,
-			"{}{}{}",
+		let word = format!(
+			"{}{}{}{}",
 			self.head
 				.as_ref()
 				.map(|v| format!("{v}"))
@@ -106,7 +103,24 @@ impl Display for Word {
 				.as_ref()
 				.map(|v| format!("{v}"))
 				.unwrap_or_default(),
-		)
+			self.comments
+				.iter()
+				.map(|i| format!(" {i}"))
+				.collect::<String>(),
+		);
+
+		match &self.invalid {
+			Some(invalid) if word.is_empty() => write!(f, "{invalid}"),
+			Some(invalid) => write!(f, "{word} {invalid}"),
+			None => write!(f, "{word}"),
+		}
+	}
+}
+
+/// The skipped code is written as it is in the source.
+impl Display for Invalid {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.text)
 	}
 }
 
@@ -126,7 +140,7 @@ impl Display for Sentence {
 
 impl Display for Comment {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-		write!(f, r#""{}""#, self.0)
+		write!(f, r#""{}""#, escape(&self.0))
 	}
 }
 
@@ -191,10 +205,7 @@ impl Display for Root {
 		write!(
 			f,
 			"{res}{}",
-			res.is_empty()
-				.not()
-				.then_some("\n")
-				.unwrap_or_default()
+			if res.is_empty() { "" } else { "\n" }
 		)
 	}
 }
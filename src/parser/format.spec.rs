@@ -662,3 +662,13 @@ fn root_must_add_trailing_newline_character_if_code_is_not_empty() {
         "#}
 	)
 }
+
+#[test]
+fn word_comments_must_follow_the_word_on_its_line() {
+	assert_eq!(
+		crate::parse_code("냥 \"a\"냥 ?\"b\" \"c\"\n냐")
+			.unwrap()
+			.to_string(),
+		"냥 \"a\" 냥? \"b\" \"c\"\n냐\n"
+	)
+}
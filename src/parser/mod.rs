@@ -1,7 +1,18 @@
-mod ast;
+pub mod ast;
+pub mod builder;
 #[macro_use]
 mod r#macro;
+mod directive;
 mod format;
+#[allow(clippy::module_inception)]
 mod parser;
+mod range;
+mod sexp;
+mod validate;
+pub mod visit;
 
-pub use parser::parse_ast;
+pub use directive::{format_with_source, verbatim_items};
+pub use parser::{parse_ast, parse_ast_recovering};
+pub use range::{apply_edits, format_range, TextEdit};
+pub use sexp::to_sexp;
+pub use validate::validate;
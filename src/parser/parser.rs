@@ -1,4 +1,9 @@
-use super::ast::{self, *};
+use std::ops::Range;
+
+use super::{
+	ast::{self, *},
+	builder::RootBuilder,
+};
 use crate::lexer::{
 	Token::{self, *},
 	TokenStream,
@@ -9,8 +14,8 @@ use nom::{
 	combinator::{eof, map, map_opt, opt, verify},
 	error::{Error, ParseError},
 	multi::{many0, many1},
-	sequence::{delimited, pair, terminated, tuple},
-	Finish, IResult, InputIter, InputTake, Parser,
+	sequence::{delimited, pair, preceded, terminated, tuple},
+	Finish, IResult, InputIter, InputLength, InputTake, Parser,
 };
 
 parse_token! { parse_inc: Inc => HeadTok::Inc => HeadTok }
@@ -71,18 +76,28 @@ where
 	E: ParseError<TokenStream<'a>>,
 {
 	map(
-		verify(
-			tuple((
-				opt(parse_head),
-				opt(parse_body),
-				opt(parse_tail),
-			)),
-			|o| !matches!(o, (None, None, None)),
+		pair(
+			verify(
+				tuple((
+					opt(parse_head),
+					opt(parse_body),
+					opt(parse_tail),
+				)),
+				|o| !matches!(o, (None, None, None)),
+			),
+			many0(parse_comment),
 		),
-		|(head, body, tail)| Word { head, body, tail },
+		|((head, body, tail), comments)| Word {
+			head,
+			body,
+			tail,
+			comments,
+			invalid: None,
+		},
 	)(input)
 }
 
+#[allow(dead_code)]
 fn parse_words0<'a, E>(
 	input: TokenStream<'a>,
 ) -> IResult<TokenStream<'a>, Sentence, E>
@@ -131,20 +146,31 @@ where
 	map_opt(take(1usize), f)
 }
 
-fn parse_comment(input: TokenStream) -> IResult<TokenStream, ast::Comment> {
+fn parse_comment<'a, E>(
+	input: TokenStream<'a>,
+) -> IResult<TokenStream<'a>, ast::Comment, E>
+where
+	E: ParseError<TokenStream<'a>>,
+{
 	map_one(match_map! { Token::Comment(s) => ast::Comment(s.clone()) })(input)
 }
 
+fn parse_comment_line(
+	input: TokenStream,
+) -> IResult<TokenStream, ast::Comment> {
+	terminated(parse_comment, opt(tag(&NewLine)))(input)
+}
+
 fn parse_comments0(
 	input: TokenStream,
 ) -> IResult<TokenStream, Vec<ast::Comment>> {
-	many0(parse_comment)(input)
+	many0(parse_comment_line)(input)
 }
 
 fn parse_comments1(
 	input: TokenStream,
 ) -> IResult<TokenStream, Vec<ast::Comment>> {
-	many1(parse_comment)(input)
+	many1(parse_comment_line)(input)
 }
 
 fn parse_paragraph(input: TokenStream) -> IResult<TokenStream, Paragraph> {
@@ -170,7 +196,10 @@ fn parse_code(input: TokenStream) -> IResult<TokenStream, Code> {
 }
 
 fn parse_root(input: TokenStream) -> IResult<TokenStream, Root> {
-	map(terminated(parse_code, eof), Root)(input)
+	map(
+		delimited(opt(tag(&NewLine)), parse_code, eof),
+		Root,
+	)(input)
 }
 
 pub fn parse_ast(input: TokenStream) -> Result<Root, Error<TokenStream>> {
@@ -179,6 +208,86 @@ pub fn parse_ast(input: TokenStream) -> Result<Root, Error<TokenStream>> {
 	Ok(o)
 }
 
+/// Adds the sentences and comments of the code after those of `builder`.
+fn append(builder: RootBuilder, code: Code) -> RootBuilder {
+	let builder = code
+		.leading_sentences
+		.into_iter()
+		.fold(builder, |builder, Sentence(words)| {
+			builder.sentence(words)
+		});
+	let builder = code.paragraphs.into_iter().fold(
+		builder,
+		|builder, Paragraph(comments, sentences)| {
+			let builder = comments
+				.into_iter()
+				.fold(builder, |builder, ast::Comment(c)| {
+					builder.comment(c)
+				});
+
+			sentences
+				.into_iter()
+				.fold(builder, |builder, Sentence(words)| {
+					builder.sentence(words)
+				})
+		},
+	);
+
+	code.trailing_comments
+		.into_iter()
+		.fold(builder, |builder, ast::Comment(c)| {
+			builder.comment(c)
+		})
+}
+
+/// Parses the tokens like [`parse_ast`], but instead of failing on a token
+/// it cannot parse, skips it along with the tokens up to the next
+/// `NewLine` or comment and goes on.
+///
+/// The skipped tokens are kept in the tree as the node `invalid` makes of
+/// their index range, after the word before them on their line. Returns
+/// the tree along with the index ranges of the skipped tokens.
+pub fn parse_ast_recovering(
+	input: TokenStream,
+	mut invalid: impl FnMut(Range<usize>) -> Invalid,
+) -> (Root, Vec<Range<usize>>) {
+	let len = input.input_len();
+	let mut builder = Root::builder();
+	let mut skipped = vec![];
+	let mut rest = input.clone();
+
+	loop {
+		let (after, code) = preceded(opt(tag(&NewLine)), parse_code)(rest)
+			.expect("parse_code consumes what it can and never fails");
+		builder = append(builder, code);
+
+		if after.input_len() == 0 {
+			break;
+		}
+
+		let start = len - after.input_len();
+		let count = 1 + after
+			.iter_elements()
+			.skip(1)
+			.take_while(|tok| !matches!(tok, NewLine | Token::Comment(_)))
+			.count();
+
+		let same_line = start > 0
+			&& !matches!(input.iter_elements().nth(start - 1), Some(NewLine));
+
+		builder = builder.invalid(invalid(start..start + count), same_line);
+		skipped.push(start..start + count);
+		rest = after.take_split(count).0;
+	}
+
+	(
+		builder
+			.build()
+			.expect("parsed sentences and comments are valid"),
+		skipped,
+	)
+}
+
 #[cfg(test)]
 #[path = "parser.spec.rs"]
 mod tests;
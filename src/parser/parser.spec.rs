@@ -539,7 +539,7 @@ fn test_parse_comment1() {
 	let code = ts![];
 
 	assert_eq!(
-		parse_comment(code).finish(),
+		parse_comment::<Error<_>>(code).finish(),
 		Err(Error::new(ts![], ErrorKind::Eof))
 	)
 }
@@ -549,7 +549,7 @@ fn test_parse_comment2() {
 	let code = ts![In];
 
 	assert_eq!(
-		parse_comment(code).finish(),
+		parse_comment::<Error<_>>(code).finish(),
 		Err(Error::new(ts![In], ErrorKind::MapOpt))
 	)
 }
@@ -560,7 +560,7 @@ fn test_parse_comment3() {
 	let code = TokenStream::from(sl);
 
 	assert_eq!(
-		parse_comment(code).finish(),
+		parse_comment::<Error<_>>(code).finish(),
 		Ok((ts![], ast::Comment(s!())))
 	)
 }
@@ -571,7 +571,7 @@ fn test_parse_comment4() {
 	let code = TokenStream::from(sl);
 
 	assert_eq!(
-		parse_comment(code).finish(),
+		parse_comment::<Error<_>>(code).finish(),
 		Ok((ts![], ast::Comment(s!("hello"))))
 	)
 }
@@ -769,6 +769,7 @@ fn parse_code_must_match_with_sentences_and_comments() {
 		Debug,
 		Out,
 		JumpRight,
+		NewLine,
 		Token::Comment(s!("co")),
 		Token::Comment(s!("mm")),
 	][..];
@@ -796,6 +797,45 @@ fn parse_code_must_match_with_sentences_and_comments() {
 	)
 }
 
+#[test]
+fn parse_code_must_match_with_sentences_and_inline_comments() {
+	let sl = &[
+		JumpLeft,
+		In,
+		NewLine,
+		Right,
+		Debug,
+		Out,
+		JumpRight,
+		Token::Comment(s!("co")),
+		Token::Comment(s!("mm")),
+	][..];
+	let code = TokenStream::from(sl);
+
+	assert_eq!(
+		parse_code(code),
+		Ok((
+			ts![],
+			Code {
+				leading_sentences: vec![
+					sentence![word!(, [BT::JumpLeft, BT::In],)],
+					sentence![
+						word!(,, [TT::Right]),
+						word!(
+							[HT::Debug],
+							[BT::Out, BT::JumpRight],;
+							"co",
+							"mm"
+						)
+					],
+				],
+				paragraphs: vec![],
+				trailing_comments: vec![],
+			}
+		))
+	)
+}
+
 #[test]
 fn parse_code_must_match_with_comments_and_sentences() {
 	let sl = &[
@@ -842,9 +882,11 @@ fn parse_code_must_match_with_c_s() {
 		In,
 		NewLine,
 		Right,
+		NewLine,
 		Token::Comment(s!("en")),
 		Debug,
 		Out,
+		NewLine,
 		Token::Comment(s!("ts")),
 		JumpRight,
 	][..];
@@ -879,20 +921,63 @@ fn parse_code_must_match_with_c_s() {
 	)
 }
 
+#[test]
+fn parse_code_must_match_with_inline_c_s() {
+	let sl = &[
+		Token::Comment(s!("co")),
+		Token::Comment(s!("mm")),
+		JumpLeft,
+		In,
+		NewLine,
+		Right,
+		Token::Comment(s!("en")),
+		Debug,
+		Out,
+		Token::Comment(s!("ts")),
+		JumpRight,
+	][..];
+	let code = TokenStream::from(sl);
+
+	assert_eq!(
+		parse_code(code),
+		Ok((
+			ts![],
+			Code {
+				leading_sentences: vec![],
+				paragraphs: vec![Paragraph(
+					vec![ast::Comment(s!("co")), ast::Comment(s!("mm")),],
+					vec![
+						sentence![word!(, [BT::JumpLeft, BT::In],)],
+						sentence![
+							word!(,, [TT::Right]; "en"),
+							word!([HT::Debug], [BT::Out],; "ts"),
+							word!(, [BT::JumpRight],)
+						],
+					]
+				)],
+				trailing_comments: vec![],
+			}
+		))
+	)
+}
+
 #[test]
 fn parse_code_must_match_with_s_c() {
 	let sl = &[
 		JumpLeft,
+		NewLine,
 		Token::Comment(s!("co")),
 		Token::Comment(s!("mm")),
 		In,
 		NewLine,
 		Right,
+		NewLine,
 		Token::Comment(s!("en")),
 		Debug,
 		Out,
 		NewLine,
 		JumpRight,
+		NewLine,
 		Token::Comment(s!("ts")),
 	][..];
 	let code = TokenStream::from(sl);
@@ -925,21 +1010,65 @@ fn parse_code_must_match_with_s_c() {
 	)
 }
 
+#[test]
+fn parse_code_must_match_with_inline_s_c() {
+	let sl = &[
+		JumpLeft,
+		Token::Comment(s!("co")),
+		Token::Comment(s!("mm")),
+		In,
+		NewLine,
+		Right,
+		Token::Comment(s!("en")),
+		Debug,
+		Out,
+		NewLine,
+		JumpRight,
+		Token::Comment(s!("ts")),
+	][..];
+	let code = TokenStream::from(sl);
+
+	assert_eq!(
+		parse_code(code),
+		Ok((
+			ts![],
+			Code {
+				leading_sentences: vec![
+					sentence![
+						word!(, [BT::JumpLeft],; "co", "mm"),
+						word!(, [BT::In],)
+					],
+					sentence![
+						word!(,, [TT::Right]; "en"),
+						word!([HT::Debug], [BT::Out],)
+					],
+					sentence![word!(, [BT::JumpRight],; "ts")],
+				],
+				paragraphs: vec![],
+				trailing_comments: vec![],
+			}
+		))
+	)
+}
+
 #[test]
 fn parse_code_must_match_with_c_c() {
 	let sl = &[
 		Token::Comment(s!("hello")),
 		JumpLeft,
+		NewLine,
 		Token::Comment(s!("co")),
 		Token::Comment(s!("mm")),
 		In,
 		NewLine,
 		Right,
+		NewLine,
 		Token::Comment(s!("en")),
 		Debug,
 		Out,
 		NewLine,
 		JumpRight,
+		NewLine,
 		Token::Comment(s!("ts")),
 	][..];
 	let code = TokenStream::from(sl);
@@ -977,8 +1106,9 @@ fn parse_code_must_match_with_c_c() {
 }
 
 #[test]
-fn parse_code_must_match_with_s_s() {
+fn parse_code_must_match_with_inline_c_c() {
 	let sl = &[
+		Token::Comment(s!("hello")),
 		JumpLeft,
 		Token::Comment(s!("co")),
 		Token::Comment(s!("mm")),
@@ -990,6 +1120,7 @@ fn parse_code_must_match_with_s_s() {
 		Out,
 		NewLine,
 		JumpRight,
+		Token::Comment(s!("ts")),
 	][..];
 	let code = TokenStream::from(sl);
 
@@ -998,23 +1129,61 @@ fn parse_code_must_match_with_s_s() {
 		Ok((
 			ts![],
 			Code {
-				leading_sentences: vec![sentence![word!(, [BT::JumpLeft],)]],
-				paragraphs: vec![
-					Paragraph(
-						vec![ast::Comment(s!("co")), ast::Comment(s!("mm")),],
-						vec![
-							sentence![word!(, [BT::In],)],
-							sentence![word!(,, [TT::Right]),],
-						]
-					),
-					Paragraph(
-						vec![ast::Comment(s!("en")),],
-						vec![
-							sentence![word!([HT::Debug], [BT::Out],)],
-							sentence![word!(, [BT::JumpRight],)],
+				leading_sentences: vec![],
+				paragraphs: vec![Paragraph(
+					vec![ast::Comment(s!("hello"))],
+					vec![
+						sentence![
+							word!(, [BT::JumpLeft],; "co", "mm"),
+							word!(, [BT::In],)
 						],
-					),
+						sentence![
+							word!(,, [TT::Right]; "en"),
+							word!([HT::Debug], [BT::Out],)
+						],
+						sentence![word!(, [BT::JumpRight],; "ts")],
+					]
+				)],
+				trailing_comments: vec![],
+			}
+		))
+	)
+}
+
+#[test]
+fn parse_code_must_match_with_s_s() {
+	let sl = &[
+		JumpLeft,
+		Token::Comment(s!("co")),
+		Token::Comment(s!("mm")),
+		In,
+		NewLine,
+		Right,
+		Token::Comment(s!("en")),
+		Debug,
+		Out,
+		NewLine,
+		JumpRight,
+	][..];
+	let code = TokenStream::from(sl);
+
+	assert_eq!(
+		parse_code(code),
+		Ok((
+			ts![],
+			Code {
+				leading_sentences: vec![
+					sentence![
+						word!(, [BT::JumpLeft],; "co", "mm"),
+						word!(, [BT::In],)
+					],
+					sentence![
+						word!(,, [TT::Right]; "en"),
+						word!([HT::Debug], [BT::Out],)
+					],
+					sentence![word!(, [BT::JumpRight],)],
 				],
+				paragraphs: vec![],
 				trailing_comments: vec![],
 			}
 		))
@@ -1032,7 +1201,7 @@ fn parse_root_must_match_with_empty_token_stream() {
 			Root(Code {
 				leading_sentences: vec![],
 				paragraphs: vec![],
-				trailing_comments: vec![]
+				trailing_comments: vec![],
 			})
 		))
 	)
@@ -1061,25 +1230,254 @@ fn test_parse_root() {
 		Ok((
 			ts![],
 			Root(Code {
-				leading_sentences: vec![sentence![word!(, [BT::JumpLeft],)]],
+				leading_sentences: vec![
+					sentence![
+						word!(, [BT::JumpLeft],; "co", "mm"),
+						word!(, [BT::In],)
+					],
+					sentence![
+						word!(,, [TT::Right]; "en"),
+						word!([HT::Debug], [BT::Out],)
+					],
+					sentence![word!(, [BT::JumpRight],; "ts")],
+				],
+				paragraphs: vec![],
+				trailing_comments: vec![],
+			})
+		))
+	)
+}
+
+#[test]
+fn parse_comments1_must_match_with_newline_separated_comments() {
+	let sl = &[
+		Token::Comment(s!("co")),
+		NewLine,
+		Token::Comment(s!("mm")),
+		NewLine,
+		JumpLeft,
+	][..];
+	let code = TokenStream::from(sl);
+
+	assert_eq!(
+		parse_comments1(code),
+		Ok((
+			ts![JumpLeft],
+			vec![ast::Comment(s!("co")), ast::Comment(s!("mm"))]
+		))
+	)
+}
+
+#[test]
+fn parse_root_must_match_with_leading_newline_and_trailing_comment_line() {
+	let sl = &[
+		NewLine,
+		Token::Comment(s!("co")),
+		NewLine,
+		In,
+		NewLine,
+		Token::Comment(s!("ts")),
+		NewLine,
+	][..];
+	let code = TokenStream::from(sl);
+
+	assert_eq!(
+		parse_root(code),
+		Ok((
+			ts![],
+			Root(Code {
+				leading_sentences: vec![],
+				paragraphs: vec![Paragraph(
+					vec![ast::Comment(s!("co"))],
+					vec![sentence![word!(, [BT::In],)]]
+				)],
+				trailing_comments: vec![ast::Comment(s!("ts"))],
+			})
+		))
+	)
+}
+
+/// Node the tests make of the index range of skipped tokens.
+fn skipped(range: Range<usize>) -> Invalid {
+	Invalid {
+		span: crate::Span::new(range.start, range.end),
+		text: format!("{range:?}"),
+		error: crate::Error::Parse {
+			at: crate::Span::new(range.start, range.start + 1),
+		},
+	}
+}
+
+/// Word holding nothing but skipped code.
+fn invalid(range: Range<usize>) -> Word {
+	Word {
+		head: None,
+		body: None,
+		tail: None,
+		comments: vec![],
+		invalid: Some(skipped(range)),
+	}
+}
+
+#[test]
+fn recovering_parser_must_skip_to_the_next_newline() {
+	let sl = &[
+		Inc,
+		Token::Unknown(s!("@")),
+		Dec,
+		NewLine,
+		Right,
+		Token::Unknown(s!("%")),
+	][..];
+
+	assert_eq!(
+		parse_ast_recovering(TokenStream::from(sl), skipped),
+		(
+			Root(Code {
+				leading_sentences: vec![
+					sentence![Word {
+						invalid: Some(skipped(1..3)),
+						..word!([HT::Inc],,)
+					}],
+					sentence![Word {
+						invalid: Some(skipped(5..6)),
+						..word!(,, [TT::Right])
+					}],
+				],
+				..Code::default()
+			}),
+			vec![1..3, 5..6]
+		)
+	)
+}
+
+#[test]
+fn recovering_parser_must_stop_skipping_at_a_comment() {
+	let sl = &[
+		Token::Comment(s!("co")),
+		Token::Unknown(s!("@")),
+		Token::Comment(s!("mm")),
+		Out,
+		NewLine,
+		NewLine,
+		NewLine,
+		Token::Unknown(s!("%")),
+	][..];
+
+	assert_eq!(
+		parse_ast_recovering(TokenStream::from(sl), skipped),
+		(
+			Root(Code {
 				paragraphs: vec![
 					Paragraph(
-						vec![ast::Comment(s!("co")), ast::Comment(s!("mm")),],
-						vec![
-							sentence![word!(, [BT::In],)],
-							sentence![word!(,, [TT::Right]),],
-						]
+						vec![ast::Comment(s!("co"))],
+						vec![sentence![invalid(1..2)]]
 					),
 					Paragraph(
-						vec![ast::Comment(s!("en")),],
+						vec![ast::Comment(s!("mm"))],
 						vec![
-							sentence![word!([HT::Debug], [BT::Out],)],
-							sentence![word!(, [BT::JumpRight],)],
-						],
+							sentence![word!(, [BT::Out],)],
+							sentence![invalid(5..6)],
+							sentence![invalid(7..8)],
+						]
 					),
 				],
-				trailing_comments: vec![ast::Comment(s!("ts"))],
+				..Code::default()
+			}),
+			vec![1..2, 5..6, 7..8]
+		)
+	)
+}
+
+#[test]
+fn recovered_code_must_be_kept_after_the_word_before_it() {
+	let code = "냥?\n냐 @@\n\"주석\"\n.";
+	let Root(parsed) = crate::parse_code_recovering(code);
+
+	assert_eq!(
+		(
+			parsed.leading_sentences[1].0[0].invalid.clone(),
+			parsed.leading_sentences.len(),
+			parsed.paragraphs.len()
+		),
+		(
+			Some(Invalid {
+				span: crate::Span::new(9, 11),
+				text: s!("@@"),
+				error: crate::Error::Lex {
+					at: crate::Span::new(9, 10)
+				},
+			}),
+			2,
+			1
+		)
+	)
+}
+
+#[test]
+fn recovered_code_must_be_formatted_as_written() {
+	assert_eq!(
+		crate::parse_code_recovering("냥 냥 @ 냐\n%%\n\"a\" ?  ? $ .")
+			.to_string(),
+		"냥냥 @ 냐\n%%\n\n\"a\"\n?? $ .\n"
+	)
+}
+
+#[test]
+fn unterminated_comment_must_be_reported_at_its_opening_quote() {
+	let code = "냥 \"주석\n?";
+
+	assert_eq!(
+		(
+			crate::parse_code(code),
+			crate::parse_code_recovering(code)
+				.0
+				.leading_sentences[0]
+				.0[0]
+				.invalid
+				.clone(),
+		),
+		(
+			Err(crate::Error::UnterminatedComment {
+				at: crate::Span::new(4, 5)
+			}),
+			Some(Invalid {
+				span: crate::Span::new(4, 11),
+				text: s!("\"주석"),
+				error: crate::Error::UnterminatedComment {
+					at: crate::Span::new(4, 5)
+				},
 			})
-		))
+		)
+	)
+}
+
+#[test]
+fn unterminated_comment_after_leading_spaces_must_be_reported_at_its_quote() {
+	assert_eq!(
+		crate::parse_code("  \"abc"),
+		Err(crate::Error::UnterminatedComment {
+			at: crate::Span::new(2, 3)
+		})
+	)
+}
+
+#[test]
+fn unterminated_comment_after_a_new_line_must_be_reported_at_its_quote() {
+	assert_eq!(
+		crate::parse_code("냥\n \t\"abc"),
+		Err(crate::Error::UnterminatedComment {
+			at: crate::Span::new(6, 7)
+		})
+	)
+}
+
+#[test]
+fn unknown_character_after_leading_spaces_must_be_reported_at_itself() {
+	assert_eq!(
+		crate::parse_code(" %"),
+		Err(crate::Error::Lex {
+			at: crate::Span::new(1, 2)
+		})
 	)
 }
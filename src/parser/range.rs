@@ -0,0 +1,95 @@
+use super::{ast::Root, directive::lines};
+use crate::{lexer::Span, FormatConfig};
+
+/// Replacement of the source code in `span` by `new_text`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextEdit {
+	pub span: Span,
+	pub new_text: String,
+}
+
+/// Formats the sentences and comments overlapping `range`, each in its
+/// own edit, leaving the rest of the source untouched, down to the
+/// whitespace and the blank lines around them.
+///
+/// The line breaks and blank lines between two lines in the range are
+/// edited as well, like the whitespace before the first line and after
+/// the last line of the code, so that formatting the whole code as a range
+/// gives the same result as formatting it at once.
+///
+/// Line breaks follow the `new-line` setting. Lines already formatted
+/// produce no edit, and the edits are sorted by their position.
+pub fn format_range(
+	root: &Root,
+	spans: &[Span],
+	source: &str,
+	range: Span,
+	config: &FormatConfig,
+) -> Vec<TextEdit> {
+	let line_break = config.new_line.resolve(source, spans);
+	let lines = lines(root, spans, source, config);
+	let in_range =
+		|span: Span| span.start <= range.end && range.start <= span.end;
+	let mut edits = vec![];
+
+	if lines.is_empty() {
+		edits.push(TextEdit {
+			span: Span::new(0, source.len()),
+			new_text: String::new(),
+		});
+	}
+	for (i, (blank, line)) in lines.iter().enumerate() {
+		if !in_range(line.span) {
+			continue;
+		}
+
+		let before = match i.checked_sub(1) {
+			None => Some((0, String::new())),
+			Some(j) if in_range(lines[j].1.span) => Some((
+				lines[j].1.span.end,
+				line_break.repeat(if *blank { 2 } else { 1 }),
+			)),
+			Some(_) => None,
+		};
+		if let Some((start, new_text)) = before {
+			edits.push(TextEdit {
+				span: Span::new(start, line.span.start),
+				new_text,
+			});
+		}
+		edits.push(TextEdit {
+			span: line.span,
+			new_text: line.text.clone(),
+		});
+		if i == lines.len() - 1 && !line.to_end {
+			edits.push(TextEdit {
+				span: Span::new(line.span.end, source.len()),
+				new_text: line_break.to_string(),
+			});
+		}
+	}
+
+	edits.retain(|edit| {
+		source[edit.span.start..edit.span.end] != edit.new_text
+	});
+	edits
+}
+
+/// Applies edits sorted by their position and not overlapping each other.
+pub fn apply_edits(source: &str, edits: &[TextEdit]) -> String {
+	let mut res = String::new();
+	let mut last = 0;
+
+	for edit in edits {
+		res.push_str(&source[last..edit.span.start]);
+		res.push_str(&edit.new_text);
+		last = edit.span.end;
+	}
+	res.push_str(&source[last..]);
+
+	res
+}
+
+#[cfg(test)]
+#[path = "range.spec.rs"]
+mod tests;
@@ -0,0 +1,177 @@
+use super::*;
+use crate::{format_code_range, Config, Span};
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+use proptest::{collection::vec, prelude::*, sample::select};
+
+const CODE: &str = indoc! {r#"
+	냥  냥 ?
+	냐 .
+
+	"첫 문단"
+	냥   ?
+
+	"둘째 문단"
+	냐   !
+	"끝"   "#};
+
+fn format_lines(first: usize, last: usize) -> String {
+	let edits = format_code_range(
+		CODE,
+		Span::lines(CODE, first, last),
+		&Config::default(),
+	)
+	.unwrap();
+
+	apply_edits(CODE, &edits)
+}
+
+#[test]
+fn only_paragraphs_in_range_must_be_formatted() {
+	assert_eq!(
+		format_lines(5, 5),
+		indoc! {r#"
+			냥  냥 ?
+			냐 .
+
+			"첫 문단"
+			냥?
+
+			"둘째 문단"
+			냐   !
+			"끝"   "#}
+	)
+}
+
+#[test]
+fn only_sentences_in_range_must_be_formatted() {
+	assert_eq!(
+		format_code_range(
+			CODE,
+			Span::lines(CODE, 2, 2),
+			&Config::default()
+		),
+		Ok(vec![TextEdit {
+			span: Span::new(11, 16),
+			new_text: String::from("냐."),
+		}])
+	)
+}
+
+#[test]
+fn blank_lines_around_the_range_must_be_kept() {
+	let code = "냥\n\n냐 ?\n\n냥 냥\n";
+	let edits = format_code_range(
+		code,
+		Span::lines(code, 3, 3),
+		&Config::default(),
+	)
+	.unwrap();
+
+	assert_eq!(
+		apply_edits(code, &edits),
+		"냥\n\n냐?\n\n냥 냥\n"
+	)
+}
+
+#[test]
+fn ranges_over_several_paragraphs_must_format_each_of_them() {
+	assert_eq!(
+		format_lines(5, 8),
+		indoc! {r#"
+			냥  냥 ?
+			냐 .
+
+			"첫 문단"
+			냥?
+
+			"둘째 문단"
+			냐!
+			"끝"   "#}
+	)
+}
+
+#[test]
+fn formatted_lines_must_have_no_edit() {
+	assert_eq!(
+		format_code_range(
+			CODE,
+			Span::lines(CODE, 4, 4),
+			&Config::default()
+		),
+		Ok(vec![])
+	);
+	assert_eq!(
+		format_code_range(
+			"냥?\n\n\"a\"\n냐\n",
+			Span::lines(CODE, 1, 4),
+			&Config::default()
+		),
+		Ok(vec![])
+	)
+}
+
+#[test]
+fn formatted_crlf_code_must_need_no_edits_with_crlf_config() {
+	let config = Config::from_toml("[format]\nnew-line = \"crlf\"").unwrap();
+	let code = crate::format_code(CODE, &config).unwrap();
+
+	assert!(code.contains("\r\n"));
+	assert_eq!(
+		format_code_range(&code, Span::new(0, code.len()), &config),
+		Ok(vec![])
+	)
+}
+
+#[test]
+fn range_edits_must_follow_new_line_config() {
+	let config = Config::from_toml("[format]\nnew-line = \"crlf\"").unwrap();
+	let code = CODE.replace('\n', "\r\n");
+	let edits =
+		format_code_range(&code, Span::new(0, code.len()), &config).unwrap();
+
+	assert!(!edits.is_empty());
+	assert!(!apply_edits(&code, &edits)
+		.replace("\r\n", "")
+		.contains('\n'))
+}
+
+#[test]
+fn lines_sharing_a_line_in_the_source_must_be_split() {
+	let code = "\"a\"냥  냥";
+	let edits = format_code_range(
+		code,
+		Span::lines(code, 1, 2),
+		&Config::default(),
+	)
+	.unwrap();
+
+	assert_eq!(apply_edits(code, &edits), "\"a\"\n냥냥\n")
+}
+
+fn code() -> impl Strategy<Value = String> {
+	vec(
+		select(vec![
+			"냥", "냐", "?", "!", ".", " ", "  ", "\n", "\n\n", "\r\n",
+			"\"a\"", "\" b  c \"", "\"nyanfmt: off\"", "\"nyanfmt: on\"",
+			"\"nyanfmt: skip\"",
+		]),
+		0..32,
+	)
+	.prop_map(|pieces| pieces.concat())
+}
+
+proptest! {
+	#[test]
+	fn formatting_the_whole_code_as_a_range_must_format_it_at_once(
+		code in code(),
+	) {
+		let config = Config::default();
+
+		prop_assert_eq!(
+			format_code_range(&code, Span::new(0, code.len()), &config)
+				.map(|edits| apply_edits(&code, &edits)),
+			crate::format_code(&code, &config)
+		);
+	}
+}
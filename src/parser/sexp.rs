@@ -0,0 +1,185 @@
+use std::{fmt::Write, iter::Copied, slice::Iter};
+
+use super::ast::*;
+use crate::lexer::Span;
+
+/// A node of the tree, covering `span` in the source.
+struct Node {
+	kind: &'static str,
+	span: Option<Span>,
+	atoms: Vec<String>,
+	children: Vec<Node>,
+}
+
+impl Node {
+	fn new(kind: &'static str, children: Vec<Node>) -> Self {
+		Self {
+			kind,
+			span: children
+				.first()
+				.zip(children.last())
+				.and_then(|(first, last)| {
+					Some(Span::new(
+						first.span?.start,
+						last.span?.end,
+					))
+				}),
+			atoms: vec![],
+			children,
+		}
+	}
+
+	fn leaf(
+		kind: &'static str,
+		atoms: Vec<String>,
+		spans: &mut Copied<Iter<Span>>,
+	) -> Self {
+		let covered = spans
+			.by_ref()
+			.take(atoms.len())
+			.collect::<Vec<_>>();
+
+		Self {
+			kind,
+			span: covered
+				.first()
+				.zip(covered.last())
+				.map(|(first, last)| Span::new(first.start, last.end)),
+			atoms,
+			children: vec![],
+		}
+	}
+
+	/// Whether the node fits on a single line, holding leaves only.
+	fn is_inline(&self) -> bool {
+		self.children
+			.iter()
+			.all(|child| child.children.is_empty())
+	}
+
+	fn write(&self, out: &mut String, depth: usize) {
+		write!(out, "({}", self.kind).unwrap();
+		if let Some(span) = self.span {
+			write!(out, " {}..{}", span.start, span.end).unwrap();
+		}
+		for atom in &self.atoms {
+			write!(out, " {atom}").unwrap();
+		}
+
+		for child in &self.children {
+			if self.is_inline() {
+				out.push(' ');
+			} else {
+				out.push('\n');
+				out.push_str(&"  ".repeat(depth + 1));
+			}
+			child.write(out, depth + 1);
+		}
+
+		out.push(')');
+	}
+}
+
+fn names<T>(toks: &[T], name: fn(&T) -> &'static str) -> Vec<String> {
+	toks.iter()
+		.map(|tok| name(tok).to_string())
+		.collect()
+}
+
+fn sentence(
+	Sentence(words): &Sentence,
+	spans: &mut Copied<Iter<Span>>,
+) -> Node {
+	let words = words
+		.iter()
+		.map(|word| {
+			let mut parts = vec![];
+			if let Some(Head(toks)) = &word.head {
+				parts.push(Node::leaf(
+					"head",
+					names(toks, HeadTok::name),
+					spans,
+				));
+			}
+			if let Some(Body(toks)) = &word.body {
+				parts.push(Node::leaf(
+					"body",
+					names(toks, BodyTok::name),
+					spans,
+				));
+			}
+			if let Some(Tail(toks)) = &word.tail {
+				parts.push(Node::leaf(
+					"tail",
+					names(toks, TailTok::name),
+					spans,
+				));
+			}
+			for c in &word.comments {
+				parts.push(comment(c, spans));
+			}
+			if let Some(invalid) = &word.invalid {
+				parts.push(Node {
+					kind: "invalid",
+					span: Some(invalid.span),
+					atoms: vec![format!("{:?}", invalid.text)],
+					children: vec![],
+				});
+			}
+
+			Node::new("word", parts)
+		})
+		.collect();
+
+	Node::new("sentence", words)
+}
+
+fn comment(Comment(comment): &Comment, spans: &mut Copied<Iter<Span>>) -> Node {
+	Node::leaf(
+		"comment",
+		vec![format!("{comment:?}")],
+		spans,
+	)
+}
+
+/// Prints the tree as S-expressions, with the byte span each node covers
+/// in the source.
+///
+/// `spans` holds the span of every token that is not a `NewLine`.
+pub fn to_sexp(Root(code): &Root, spans: &[Span]) -> String {
+	let mut spans = spans.iter().copied();
+	let mut children = vec![];
+
+	for s in &code.leading_sentences {
+		children.push(sentence(s, &mut spans));
+	}
+	for Paragraph(comments, sentences) in &code.paragraphs {
+		let mut parts = vec![];
+		for c in comments {
+			parts.push(comment(c, &mut spans));
+		}
+		for s in sentences {
+			parts.push(sentence(s, &mut spans));
+		}
+		children.push(Node::new("paragraph", parts));
+	}
+	for c in &code.trailing_comments {
+		children.push(comment(c, &mut spans));
+	}
+
+	let mut out = String::new();
+	Node {
+		kind: "root",
+		span: None,
+		atoms: vec![],
+		children,
+	}
+	.write(&mut out, 0);
+	out.push('\n');
+
+	out
+}
+
+#[cfg(test)]
+#[path = "sexp.spec.rs"]
+mod tests;
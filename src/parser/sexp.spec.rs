@@ -0,0 +1,64 @@
+use super::*;
+use crate::{parse_code_with_spans, UnknownChars};
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+fn sexp(code: &str) -> String {
+	let (root, spans) =
+		parse_code_with_spans(code, UnknownChars::Error).unwrap();
+
+	to_sexp(&root, &spans)
+}
+
+#[test]
+fn nodes_must_carry_spans() {
+	assert_eq!(
+		sexp("냥냥? ~.-\n\"주석\"\n냥뀨!\n\"끝\""),
+		indoc! {r#"
+			(root
+			  (sentence 0..11
+			    (word 0..7 (head 0..6 inc inc) (tail 6..7 right))
+			    (word 8..11 (body 8..11 jump_right out jump_left)))
+			  (paragraph 12..28
+			    (comment 12..20 "주석")
+			    (sentence 21..28
+			      (word 21..28 (head 21..27 inc debug) (tail 27..28 left))))
+			  (comment 29..34 "끝"))
+		"#}
+	)
+}
+
+#[test]
+fn empty_code_must_print_empty_root() {
+	assert_eq!(sexp(""), "(root)\n")
+}
+
+#[test]
+fn word_comments_must_be_leaves_of_the_word() {
+	assert_eq!(
+		sexp("냥 \"a\" ?"),
+		indoc! {r#"
+			(root
+			  (sentence 0..9
+			    (word 0..7 (head 0..3 inc) (comment 4..7 "a"))
+			    (word 8..9 (tail 8..9 right))))
+		"#}
+	)
+}
+
+#[test]
+fn skipped_code_must_be_a_leaf_of_the_word_before_it() {
+	let (root, spans) =
+		crate::parse_code_recovering_with_spans("냥 @@\n?", UnknownChars::Error);
+
+	assert_eq!(
+		to_sexp(&root, &spans),
+		indoc! {r#"
+			(root
+			  (sentence 0..6
+			    (word 0..6 (head 0..3 inc) (invalid 4..6 "@@")))
+			  (sentence 7..8
+			    (word 7..8 (tail 7..8 right))))
+		"#}
+	)
+}
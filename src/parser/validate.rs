@@ -0,0 +1,97 @@
+use super::ast::*;
+use crate::Error;
+
+fn invalid(path: String, message: &str) -> Error {
+	Error::InvalidAst(format!("{path}: {message}"))
+}
+
+fn sentence(path: String, Sentence(words): &Sentence) -> Result<(), Error> {
+	if words.is_empty() {
+		return Err(invalid(path, "sentence has no words"));
+	}
+
+	for (i, word) in words.iter().enumerate() {
+		let path = format!("{path}[{i}]");
+		let parts = [
+			(
+				"head",
+				word.head
+					.as_ref()
+					.map(|Head(v)| v.len()),
+			),
+			(
+				"body",
+				word.body
+					.as_ref()
+					.map(|Body(v)| v.len()),
+			),
+			(
+				"tail",
+				word.tail
+					.as_ref()
+					.map(|Tail(v)| v.len()),
+			),
+		];
+
+		if parts
+			.iter()
+			.all(|(_, len)| len.is_none())
+			&& word.invalid.is_none()
+		{
+			return Err(invalid(
+				path,
+				"word has no head, body or tail",
+			));
+		}
+		if let Some((part, _)) = parts
+			.iter()
+			.find(|(_, len)| *len == Some(0))
+		{
+			return Err(invalid(
+				format!("{path}.{part}"),
+				"token list is empty, use null instead",
+			));
+		}
+	}
+
+	Ok(())
+}
+
+/// Checks that the tree could come from parsing code, so that formatting
+/// it gives code parsing back into the same instructions and comments.
+pub fn validate(Root(code): &Root) -> Result<(), Error> {
+	for (i, s) in code
+		.leading_sentences
+		.iter()
+		.enumerate()
+	{
+		sentence(format!("leading_sentences[{i}]"), s)?;
+	}
+	for (i, Paragraph(comments, sentences)) in
+		code.paragraphs.iter().enumerate()
+	{
+		let path = format!("paragraphs[{i}]");
+
+		if comments.is_empty() {
+			return Err(invalid(
+				path,
+				"paragraph has no comments",
+			));
+		}
+		if sentences.is_empty() {
+			return Err(invalid(
+				path,
+				"paragraph has no sentences",
+			));
+		}
+		for (j, s) in sentences.iter().enumerate() {
+			sentence(format!("{path}.sentences[{j}]"), s)?;
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+#[path = "validate.spec.rs"]
+mod tests;
@@ -0,0 +1,83 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+fn word(head: Option<Vec<HeadTok>>) -> Word {
+	Word {
+		head: head.map(Head),
+		body: None,
+		tail: None,
+		comments: vec![],
+		invalid: None,
+	}
+}
+
+fn root(paragraphs: Vec<Paragraph>) -> Root {
+	Root(Code {
+		paragraphs,
+		..Code::default()
+	})
+}
+
+#[test]
+fn parsed_tree_must_be_valid() {
+	assert_eq!(
+		validate(&crate::parse_code("냥? \"주석\"\n~.-!\n\"끝\"").unwrap()),
+		Ok(())
+	)
+}
+
+#[test]
+fn quotes_in_comments_must_be_accepted() {
+	assert_eq!(
+		validate(&root(vec![Paragraph(
+			vec![Comment(String::from("a\"b"))],
+			vec![Sentence(vec![Word {
+				comments: vec![Comment(String::from("\"\\"))],
+				..word(Some(vec![HeadTok::Inc]))
+			}])],
+		)])),
+		Ok(())
+	)
+}
+
+#[test]
+fn empty_word_must_be_rejected() {
+	assert_eq!(
+		validate(&root(vec![Paragraph(
+			vec![Comment(String::from("a"))],
+			vec![Sentence(vec![
+				word(Some(vec![HeadTok::Inc])),
+				word(None),
+			])],
+		)])),
+		Err(Error::InvalidAst(String::from(
+			"paragraphs[0].sentences[0][1]: word has no head, body or tail"
+		)))
+	)
+}
+
+#[test]
+fn empty_token_list_must_be_rejected() {
+	assert_eq!(
+		validate(&Root(Code {
+			leading_sentences: vec![Sentence(vec![word(Some(vec![]))])],
+			..Code::default()
+		})),
+		Err(Error::InvalidAst(String::from(
+			"leading_sentences[0][0].head: token list is empty, use null instead"
+		)))
+	)
+}
+
+#[test]
+fn paragraph_without_comments_must_be_rejected() {
+	assert_eq!(
+		validate(&root(vec![Paragraph(
+			vec![],
+			vec![Sentence(vec![word(Some(vec![HeadTok::Inc]))])],
+		)])),
+		Err(Error::InvalidAst(String::from(
+			"paragraphs[0]: paragraph has no comments"
+		)))
+	)
+}
@@ -0,0 +1,303 @@
+//! Traversal of the syntax tree.
+//!
+//! Every `visit_*` method of [`Visitor`] and [`VisitorMut`] walks into the
+//! children of its node by default, through the `walk_*` function of the
+//! same name. Overriding a method stops the walk at that node, unless the
+//! override calls the `walk_*` function itself.
+//!
+//! Nodes are visited in source order: the leading sentences, then the
+//! comments and sentences of every paragraph, then the trailing comments.
+//! The comments of a word come after its tokens, followed by the code the
+//! recovering parser skipped after it.
+//!
+//! ```
+//! use nyanfmt::{ast::*, parse_code, visit::Visitor};
+//!
+//! struct Comments<'ast>(Vec<&'ast str>);
+//!
+//! impl<'ast> Visitor<'ast> for Comments<'ast> {
+//!     fn visit_comment(&mut self, Comment(comment): &'ast Comment) {
+//!         self.0.push(comment);
+//!     }
+//! }
+//!
+//! let root = parse_code("\"a\"\n냥\n\n\"b\"").unwrap();
+//! let mut comments = Comments(vec![]);
+//! comments.visit_root(&root);
+//!
+//! assert_eq!(comments.0, ["a", "b"]);
+//! ```
+
+use super::ast::*;
+
+pub trait Visitor<'ast> {
+	fn visit_root(&mut self, root: &'ast Root) {
+		walk_root(self, root)
+	}
+
+	fn visit_code(&mut self, code: &'ast Code) {
+		walk_code(self, code)
+	}
+
+	fn visit_paragraph(&mut self, paragraph: &'ast Paragraph) {
+		walk_paragraph(self, paragraph)
+	}
+
+	fn visit_sentence(&mut self, sentence: &'ast Sentence) {
+		walk_sentence(self, sentence)
+	}
+
+	fn visit_word(&mut self, word: &'ast Word) {
+		walk_word(self, word)
+	}
+
+	fn visit_head(&mut self, head: &'ast Head) {
+		walk_head(self, head)
+	}
+
+	fn visit_body(&mut self, body: &'ast Body) {
+		walk_body(self, body)
+	}
+
+	fn visit_tail(&mut self, tail: &'ast Tail) {
+		walk_tail(self, tail)
+	}
+
+	fn visit_head_tok(&mut self, _tok: &'ast HeadTok) {}
+
+	fn visit_body_tok(&mut self, _tok: &'ast BodyTok) {}
+
+	fn visit_tail_tok(&mut self, _tok: &'ast TailTok) {}
+
+	fn visit_comment(&mut self, _comment: &'ast Comment) {}
+
+	fn visit_invalid(&mut self, _invalid: &'ast Invalid) {}
+}
+
+pub fn walk_root<'ast, V: Visitor<'ast> + ?Sized>(
+	visitor: &mut V,
+	Root(code): &'ast Root,
+) {
+	visitor.visit_code(code);
+}
+
+pub fn walk_code<'ast, V: Visitor<'ast> + ?Sized>(
+	visitor: &mut V,
+	code: &'ast Code,
+) {
+	for sentence in &code.leading_sentences {
+		visitor.visit_sentence(sentence);
+	}
+	for paragraph in &code.paragraphs {
+		visitor.visit_paragraph(paragraph);
+	}
+	for comment in &code.trailing_comments {
+		visitor.visit_comment(comment);
+	}
+}
+
+pub fn walk_paragraph<'ast, V: Visitor<'ast> + ?Sized>(
+	visitor: &mut V,
+	Paragraph(comments, sentences): &'ast Paragraph,
+) {
+	for comment in comments {
+		visitor.visit_comment(comment);
+	}
+	for sentence in sentences {
+		visitor.visit_sentence(sentence);
+	}
+}
+
+pub fn walk_sentence<'ast, V: Visitor<'ast> + ?Sized>(
+	visitor: &mut V,
+	Sentence(words): &'ast Sentence,
+) {
+	for word in words {
+		visitor.visit_word(word);
+	}
+}
+
+pub fn walk_word<'ast, V: Visitor<'ast> + ?Sized>(
+	visitor: &mut V,
+	word: &'ast Word,
+) {
+	if let Some(head) = &word.head {
+		visitor.visit_head(head);
+	}
+	if let Some(body) = &word.body {
+		visitor.visit_body(body);
+	}
+	if let Some(tail) = &word.tail {
+		visitor.visit_tail(tail);
+	}
+	for comment in &word.comments {
+		visitor.visit_comment(comment);
+	}
+	if let Some(invalid) = &word.invalid {
+		visitor.visit_invalid(invalid);
+	}
+}
+
+pub fn walk_head<'ast, V: Visitor<'ast> + ?Sized>(
+	visitor: &mut V,
+	Head(toks): &'ast Head,
+) {
+	for tok in toks {
+		visitor.visit_head_tok(tok);
+	}
+}
+
+pub fn walk_body<'ast, V: Visitor<'ast> + ?Sized>(
+	visitor: &mut V,
+	Body(toks): &'ast Body,
+) {
+	for tok in toks {
+		visitor.visit_body_tok(tok);
+	}
+}
+
+pub fn walk_tail<'ast, V: Visitor<'ast> + ?Sized>(
+	visitor: &mut V,
+	Tail(toks): &'ast Tail,
+) {
+	for tok in toks {
+		visitor.visit_tail_tok(tok);
+	}
+}
+
+/// Like [`Visitor`], but able to rewrite the nodes it visits.
+///
+/// Rewriting a node may leave a tree that parsing code could not give,
+/// such as a word without any token, which formats to surprising code.
+pub trait VisitorMut {
+	fn visit_root_mut(&mut self, root: &mut Root) {
+		walk_root_mut(self, root)
+	}
+
+	fn visit_code_mut(&mut self, code: &mut Code) {
+		walk_code_mut(self, code)
+	}
+
+	fn visit_paragraph_mut(&mut self, paragraph: &mut Paragraph) {
+		walk_paragraph_mut(self, paragraph)
+	}
+
+	fn visit_sentence_mut(&mut self, sentence: &mut Sentence) {
+		walk_sentence_mut(self, sentence)
+	}
+
+	fn visit_word_mut(&mut self, word: &mut Word) {
+		walk_word_mut(self, word)
+	}
+
+	fn visit_head_mut(&mut self, head: &mut Head) {
+		walk_head_mut(self, head)
+	}
+
+	fn visit_body_mut(&mut self, body: &mut Body) {
+		walk_body_mut(self, body)
+	}
+
+	fn visit_tail_mut(&mut self, tail: &mut Tail) {
+		walk_tail_mut(self, tail)
+	}
+
+	fn visit_head_tok_mut(&mut self, _tok: &mut HeadTok) {}
+
+	fn visit_body_tok_mut(&mut self, _tok: &mut BodyTok) {}
+
+	fn visit_tail_tok_mut(&mut self, _tok: &mut TailTok) {}
+
+	fn visit_comment_mut(&mut self, _comment: &mut Comment) {}
+
+	fn visit_invalid_mut(&mut self, _invalid: &mut Invalid) {}
+}
+
+pub fn walk_root_mut<V: VisitorMut + ?Sized>(
+	visitor: &mut V,
+	Root(code): &mut Root,
+) {
+	visitor.visit_code_mut(code);
+}
+
+pub fn walk_code_mut<V: VisitorMut + ?Sized>(visitor: &mut V, code: &mut Code) {
+	for sentence in &mut code.leading_sentences {
+		visitor.visit_sentence_mut(sentence);
+	}
+	for paragraph in &mut code.paragraphs {
+		visitor.visit_paragraph_mut(paragraph);
+	}
+	for comment in &mut code.trailing_comments {
+		visitor.visit_comment_mut(comment);
+	}
+}
+
+pub fn walk_paragraph_mut<V: VisitorMut + ?Sized>(
+	visitor: &mut V,
+	Paragraph(comments, sentences): &mut Paragraph,
+) {
+	for comment in comments {
+		visitor.visit_comment_mut(comment);
+	}
+	for sentence in sentences {
+		visitor.visit_sentence_mut(sentence);
+	}
+}
+
+pub fn walk_sentence_mut<V: VisitorMut + ?Sized>(
+	visitor: &mut V,
+	Sentence(words): &mut Sentence,
+) {
+	for word in words {
+		visitor.visit_word_mut(word);
+	}
+}
+
+pub fn walk_word_mut<V: VisitorMut + ?Sized>(visitor: &mut V, word: &mut Word) {
+	if let Some(head) = &mut word.head {
+		visitor.visit_head_mut(head);
+	}
+	if let Some(body) = &mut word.body {
+		visitor.visit_body_mut(body);
+	}
+	if let Some(tail) = &mut word.tail {
+		visitor.visit_tail_mut(tail);
+	}
+	for comment in &mut word.comments {
+		visitor.visit_comment_mut(comment);
+	}
+	if let Some(invalid) = &mut word.invalid {
+		visitor.visit_invalid_mut(invalid);
+	}
+}
+
+pub fn walk_head_mut<V: VisitorMut + ?Sized>(
+	visitor: &mut V,
+	Head(toks): &mut Head,
+) {
+	for tok in toks {
+		visitor.visit_head_tok_mut(tok);
+	}
+}
+
+pub fn walk_body_mut<V: VisitorMut + ?Sized>(
+	visitor: &mut V,
+	Body(toks): &mut Body,
+) {
+	for tok in toks {
+		visitor.visit_body_tok_mut(tok);
+	}
+}
+
+pub fn walk_tail_mut<V: VisitorMut + ?Sized>(
+	visitor: &mut V,
+	Tail(toks): &mut Tail,
+) {
+	for tok in toks {
+		visitor.visit_tail_tok_mut(tok);
+	}
+}
+
+#[cfg(test)]
+#[path = "visit.spec.rs"]
+mod tests;
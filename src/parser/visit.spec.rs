@@ -0,0 +1,132 @@
+use super::*;
+use crate::{parse_code, parse_code_recovering};
+use pretty_assertions::assert_eq;
+
+/// Names of the visited nodes, in order.
+#[derive(Default)]
+struct Trace(Vec<String>);
+
+impl<'ast> Visitor<'ast> for Trace {
+	fn visit_paragraph(&mut self, paragraph: &'ast Paragraph) {
+		self.0.push(String::from("paragraph"));
+		walk_paragraph(self, paragraph);
+	}
+
+	fn visit_sentence(&mut self, sentence: &'ast Sentence) {
+		self.0.push(String::from("sentence"));
+		walk_sentence(self, sentence);
+	}
+
+	fn visit_head_tok(&mut self, tok: &'ast HeadTok) {
+		self.0.push(tok.name().to_string());
+	}
+
+	fn visit_body_tok(&mut self, tok: &'ast BodyTok) {
+		self.0.push(tok.name().to_string());
+	}
+
+	fn visit_tail_tok(&mut self, tok: &'ast TailTok) {
+		self.0.push(tok.name().to_string());
+	}
+
+	fn visit_comment(&mut self, Comment(comment): &'ast Comment) {
+		self.0.push(format!("{comment:?}"));
+	}
+
+	fn visit_invalid(&mut self, invalid: &'ast Invalid) {
+		self.0.push(format!("invalid {:?}", invalid.text));
+	}
+}
+
+#[test]
+fn nodes_must_be_visited_in_source_order() {
+	let root = parse_code("냥? \"a\"\n\"p\"\n~.-!\n\n\"b\"").unwrap();
+	let mut trace = Trace::default();
+	trace.visit_root(&root);
+
+	assert_eq!(
+		trace.0,
+		[
+			"sentence",
+			"inc",
+			"right",
+			"\"a\"",
+			"paragraph",
+			"\"p\"",
+			"sentence",
+			"jump_right",
+			"out",
+			"jump_left",
+			"left",
+			"\"b\"",
+		]
+	)
+}
+
+#[test]
+fn skipped_code_must_be_visited_after_its_word() {
+	let root = parse_code_recovering("냥 \"a\" @@\n%\n?");
+	let mut trace = Trace::default();
+	trace.visit_root(&root);
+
+	assert_eq!(
+		trace.0,
+		[
+			"sentence",
+			"inc",
+			"\"a\"",
+			"invalid \"@@\"",
+			"sentence",
+			"invalid \"%\"",
+			"sentence",
+			"right",
+		]
+	)
+}
+
+#[test]
+fn overriding_must_stop_walk() {
+	struct Words(usize);
+
+	impl<'ast> Visitor<'ast> for Words {
+		fn visit_word(&mut self, _word: &'ast Word) {
+			self.0 += 1;
+		}
+
+		fn visit_head_tok(&mut self, _tok: &'ast HeadTok) {
+			unreachable!()
+		}
+	}
+
+	let mut words = Words(0);
+	words.visit_root(&parse_code("냥냥 냥? 냐!").unwrap());
+
+	assert_eq!(words.0, 2)
+}
+
+#[test]
+fn visitor_mut_must_rewrite_tree() {
+	/// Swaps the direction of every move.
+	struct Mirror;
+
+	impl VisitorMut for Mirror {
+		fn visit_tail_tok_mut(&mut self, tok: &mut TailTok) {
+			*tok = match tok {
+				TailTok::Right => TailTok::Left,
+				TailTok::Left => TailTok::Right,
+			};
+		}
+
+		fn visit_comment_mut(&mut self, Comment(comment): &mut Comment) {
+			*comment = comment.to_uppercase();
+		}
+	}
+
+	let mut root = parse_code("\"mirror\"\n냥?? 냐!").unwrap();
+	Mirror.visit_root_mut(&mut root);
+
+	assert_eq!(
+		root.to_string(),
+		"\"MIRROR\"\n냥!! 냐?\n"
+	)
+}
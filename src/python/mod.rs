@@ -0,0 +1,94 @@
+//! Python bindings, built into the `nyanfmt` module by
+//! `maturin build --features python`.
+
+use pyo3::{
+	create_exception,
+	exceptions::PyValueError,
+	prelude::*,
+	types::PyBytes,
+};
+
+use crate::{parse_code, parser::ast::Root, Config, Error};
+
+create_exception!(
+	nyanfmt,
+	NyanfmtError,
+	PyValueError,
+	"Failure to format, parse or run the code, with `args` holding the message and the `(start, end)` character offsets of where the code failed, or `None`."
+);
+
+fn error(code: &str, err: Error) -> PyErr {
+	let chars = |offset: usize| code[..offset].chars().count();
+
+	NyanfmtError::new_err((
+		err.to_string(),
+		err.span()
+			.map(|span| (chars(span.start), chars(span.end))),
+	))
+}
+
+/// The code as a dict of `leading_sentences`, `paragraphs` and
+/// `trailing_comments`, in the schema of the JSON tree, which it is
+/// converted from.
+fn root<'py>(py: Python<'py>, root: &Root) -> PyResult<Bound<'py, PyAny>> {
+	py.import("json")?
+		.call_method1("loads", (serde_json::to_string(root).unwrap(),))
+}
+
+/// Input given to the program, as bytes or as text encoded in UTF-8.
+#[derive(FromPyObject)]
+enum Stdin {
+	Text(String),
+	Bytes(Vec<u8>),
+}
+
+#[pyfunction]
+fn format(code: &str) -> PyResult<String> {
+	crate::format_code(code, &Config::default()).map_err(|err| error(code, err))
+}
+
+#[pyfunction]
+fn parse<'py>(py: Python<'py>, code: &str) -> PyResult<Bound<'py, PyAny>> {
+	root(
+		py,
+		&parse_code(code).map_err(|err| error(code, err))?,
+	)
+}
+
+/// Runs the code, returning what it wrote.
+#[pyfunction]
+#[pyo3(signature = (code, stdin = None))]
+fn run<'py>(
+	py: Python<'py>,
+	code: &str,
+	stdin: Option<Stdin>,
+) -> PyResult<Bound<'py, PyBytes>> {
+	let input = match stdin {
+		Some(Stdin::Text(text)) => text.into_bytes(),
+		Some(Stdin::Bytes(bytes)) => bytes,
+		None => vec![],
+	};
+	let mut output = vec![];
+
+	crate::run_code(code, &input[..], &mut output)
+		.map_err(|err| error(code, err))?;
+
+	Ok(PyBytes::new(py, &output))
+}
+
+#[pymodule]
+fn nyanfmt(module: &Bound<'_, PyModule>) -> PyResult<()> {
+	module.add_function(wrap_pyfunction!(format, module)?)?;
+	module.add_function(wrap_pyfunction!(parse, module)?)?;
+	module.add_function(wrap_pyfunction!(run, module)?)?;
+	module.add(
+		"NyanfmtError",
+		module.py().get_type::<NyanfmtError>(),
+	)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+#[path = "python.spec.rs"]
+mod tests;
@@ -0,0 +1,102 @@
+use std::ffi::CStr;
+
+use super::*;
+use pyo3::types::PyDict;
+
+/// Runs the Python code with the module imported as `nyanfmt`.
+fn python(code: &CStr) {
+	Python::initialize();
+	Python::attach(|py| {
+		let module = PyModule::new(py, "nyanfmt").unwrap();
+		nyanfmt(&module).unwrap();
+
+		let locals = PyDict::new(py);
+		locals
+			.set_item("nyanfmt", module)
+			.unwrap();
+
+		if let Err(err) = py.run(code, None, Some(&locals)) {
+			panic!("{err}");
+		}
+	})
+}
+
+#[test]
+fn format_must_return_formatted_code() {
+	python(
+		cr#"
+assert nyanfmt.format('"주석"\n냥냥 냥~?') == '"주석"\n냥냥냥~?\n'
+"#,
+	)
+}
+
+#[test]
+fn errors_must_point_at_characters() {
+	python(
+		cr#"
+try:
+    nyanfmt.format("냥냥\n냥a")
+    assert False
+except nyanfmt.NyanfmtError as err:
+    assert isinstance(err, ValueError)
+    assert err.args == ("failed to tokenize the code", (4, 5))
+"#,
+	)
+}
+
+#[test]
+fn parse_must_return_nested_dicts() {
+	python(
+		cr#"
+assert nyanfmt.parse('냥?\n"주석"\n냥뀨~!') == {
+    "leading_sentences": [
+        [{"head": ["inc"], "body": None, "tail": ["right"]}],
+    ],
+    "paragraphs": [
+        {
+            "comments": ["주석"],
+            "sentences": [
+                [{"head": ["inc", "debug"], "body": ["jump_right"], "tail": ["left"]}],
+            ],
+        },
+    ],
+    "trailing_comments": [],
+}
+"#,
+	)
+}
+
+#[test]
+fn parse_must_give_word_comments() {
+	python(
+		cr#"
+assert nyanfmt.parse('냥? "주석"')["leading_sentences"] == [
+    [{"head": ["inc"], "body": None, "tail": ["right"], "comments": ["주석"]}],
+]
+"#,
+	)
+}
+
+#[test]
+fn run_must_read_given_stdin() {
+	python(
+		cr#"
+assert nyanfmt.run(",.,.", stdin=b"ab") == b"ab"
+assert nyanfmt.run(",.,.,.", stdin="냥") == "냥".encode()
+assert nyanfmt.run(",.") == b"\0"
+"#,
+	)
+}
+
+#[test]
+fn runtime_errors_must_have_no_span() {
+	python(
+		cr#"
+try:
+    nyanfmt.run("냥!")
+    assert False
+except nyanfmt.NyanfmtError as err:
+    assert err.args[1] is None
+"#,
+	)
+}
@@ -0,0 +1,35 @@
+use crate::parser::ast::*;
+
+/// Brainfuck instructions of the word, with `뀨` as the `#` debug command
+/// understood by many Brainfuck interpreters.
+pub fn word_to_brainfuck(word: &Word) -> String {
+	let head = word
+		.head
+		.iter()
+		.flat_map(|Head(v)| v)
+		.map(|tok| match tok {
+			HeadTok::Inc => '+',
+			HeadTok::Dec => '-',
+			HeadTok::Debug => '#',
+		});
+	let body = word
+		.body
+		.iter()
+		.flat_map(|Body(v)| v)
+		.map(|tok| match tok {
+			BodyTok::Out => '.',
+			BodyTok::In => ',',
+			BodyTok::JumpRight => '[',
+			BodyTok::JumpLeft => ']',
+		});
+	let tail = word
+		.tail
+		.iter()
+		.flat_map(|Tail(v)| v)
+		.map(|tok| match tok {
+			TailTok::Right => '>',
+			TailTok::Left => '<',
+		});
+
+	head.chain(body).chain(tail).collect()
+}
@@ -0,0 +1,7 @@
+#[cfg(feature = "lsp")]
+mod brainfuck;
+mod wat;
+
+#[cfg(feature = "lsp")]
+pub use brainfuck::word_to_brainfuck;
+pub use wat::to_wat;
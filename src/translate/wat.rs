@@ -0,0 +1,130 @@
+use std::fmt::Write;
+
+use crate::ir::Op;
+
+const CELL: &str = "(i32.load8_u (local.get $p))";
+
+struct Emitter {
+	out: String,
+	depth: usize,
+}
+
+impl Emitter {
+	fn new() -> Self {
+		Self {
+			out: String::new(),
+			depth: 2,
+		}
+	}
+
+	fn line(&mut self, line: &str) {
+		writeln!(
+			self.out,
+			"{}{line}",
+			"  ".repeat(self.depth)
+		)
+		.unwrap();
+	}
+
+	fn op(&mut self, i: usize, op: &Op) {
+		match *op {
+			Op::Add(n) => self.line(&format!(
+				"(i32.store8 (local.get $p) (i32.add {CELL} (i32.const {n})))"
+			)),
+			Op::Move(n) => {
+				self.line(&format!(
+					"(local.set $p (i32.add (local.get $p) (i32.const {n})))"
+				));
+				if n > 0 {
+					self.line("(call $reserve (local.get $p))");
+				}
+			},
+			Op::Set(n) => self.line(&format!(
+				"(i32.store8 (local.get $p) (i32.const {n}))"
+			)),
+			Op::MulAdd { offset, factor } => {
+				let target =
+					format!("(i32.add (local.get $p) (i32.const {offset}))");
+
+				// Like the interpreter, leave the target alone when the
+				// loop would not run, as it may lie outside the memory.
+				self.line(&format!(
+					"(if (i32.ne {CELL} (i32.const 0))"
+				));
+				self.depth += 1;
+				self.line("(then");
+				self.depth += 1;
+				if offset > 0 {
+					self.line(&format!("(call $reserve {target})"));
+				}
+				self.line(&format!(
+					"(i32.store8 {target} (i32.add (i32.load8_u {target}) (i32.mul {CELL} (i32.const {factor}))))"
+				));
+				self.depth -= 1;
+				self.line(")");
+				self.depth -= 1;
+				self.line(")");
+			},
+			Op::Out => self.line(&format!("(call $write {CELL})")),
+			Op::In => self.line("(i32.store8 (local.get $p) (call $read))"),
+			Op::JumpRight(_) => {
+				self.line(&format!("(block $break{i}"));
+				self.depth += 1;
+				self.line(&format!("(loop $continue{i}"));
+				self.depth += 1;
+				self.line(&format!(
+					"(br_if $break{i} (i32.eqz {CELL}))"
+				));
+			},
+			Op::JumpLeft(start) => {
+				self.line(&format!("(br $continue{start})"));
+				self.depth -= 1;
+				self.line(")");
+				self.depth -= 1;
+				self.line(")");
+			},
+		}
+	}
+}
+
+/// Lowers the program into a WebAssembly text module.
+///
+/// The tape lives in the exported linear memory, one byte per cell,
+/// starting at address 0. The memory starts with one page and grows
+/// whenever the pointer moves right past its end, like the tape of the
+/// interpreter does, trapping only once it can't grow anymore. Input and output go through the imported
+/// `env.read` and `env.write` functions, and the program itself is
+/// exported as `main`.
+pub fn to_wat(ops: &[Op]) -> String {
+	let mut emitter = Emitter::new();
+
+	for (i, op) in ops.iter().enumerate() {
+		emitter.op(i, op);
+	}
+
+	format!(
+		r#"(module
+  (import "env" "read" (func $read (result i32)))
+  (import "env" "write" (func $write (param i32)))
+  (memory (export "memory") 1)
+  (func $reserve (param $end i32)
+    (if (i32.ge_u (local.get $end) (i32.shl (memory.size) (i32.const 16)))
+      (then
+        (if (i32.eq (memory.grow (i32.sub (i32.add (i32.shr_u (local.get $end) (i32.const 16)) (i32.const 1)) (memory.size))) (i32.const -1))
+          (then unreachable)
+        )
+      )
+    )
+  )
+  (func (export "main")
+    (local $p i32)
+{}  )
+)
+"#,
+		emitter.out
+	)
+}
+
+#[cfg(test)]
+#[path = "wat.spec.rs"]
+mod tests;
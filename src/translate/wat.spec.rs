@@ -0,0 +1,211 @@
+use super::*;
+use crate::{ir::compile, parse_code};
+use pretty_assertions::assert_eq;
+use str_macro::str as s;
+use wasmi::{Caller, Engine, Linker, Module, Store};
+
+#[derive(Default)]
+struct Io {
+	input: Vec<u8>,
+	output: Vec<u8>,
+}
+
+fn run(code: &str, input: &[u8]) -> Vec<u8> {
+	let wat = to_wat(&compile(&parse_code(code).unwrap()).unwrap());
+
+	let engine = Engine::default();
+	let module = Module::new(&engine, &::wat::parse_str(wat).unwrap()).unwrap();
+	let mut store = Store::new(
+		&engine,
+		Io {
+			input: input.iter().rev().copied().collect(),
+			..Default::default()
+		},
+	);
+
+	let mut linker = <Linker<Io>>::new(&engine);
+	linker
+		.func_wrap(
+			"env",
+			"read",
+			|mut caller: Caller<'_, Io>| {
+				caller
+					.data_mut()
+					.input
+					.pop()
+					.unwrap_or_default() as i32
+			},
+		)
+		.unwrap();
+	linker
+		.func_wrap(
+			"env",
+			"write",
+			|mut caller: Caller<'_, Io>, b: i32| {
+				caller.data_mut().output.push(b as u8)
+			},
+		)
+		.unwrap();
+
+	let instance = linker
+		.instantiate(&mut store, &module)
+		.unwrap()
+		.start(&mut store)
+		.unwrap();
+	instance
+		.get_typed_func::<(), ()>(&store, "main")
+		.unwrap()
+		.call(&mut store, ())
+		.unwrap();
+
+	store.into_data().output
+}
+
+#[test]
+fn empty_program_must_produce_empty_main() {
+	assert_eq!(
+		to_wat(&compile(&parse_code("").unwrap()).unwrap()),
+		s!(r#"(module
+  (import "env" "read" (func $read (result i32)))
+  (import "env" "write" (func $write (param i32)))
+  (memory (export "memory") 1)
+  (func $reserve (param $end i32)
+    (if (i32.ge_u (local.get $end) (i32.shl (memory.size) (i32.const 16)))
+      (then
+        (if (i32.eq (memory.grow (i32.sub (i32.add (i32.shr_u (local.get $end) (i32.const 16)) (i32.const 1)) (memory.size))) (i32.const -1))
+          (then unreachable)
+        )
+      )
+    )
+  )
+  (func (export "main")
+    (local $p i32)
+  )
+)
+"#)
+	)
+}
+
+#[test]
+fn loops_must_be_lowered_into_nested_blocks() {
+	assert_eq!(
+		to_wat(&compile(&parse_code("~냐.-").unwrap()).unwrap()),
+		s!(r#"(module
+  (import "env" "read" (func $read (result i32)))
+  (import "env" "write" (func $write (param i32)))
+  (memory (export "memory") 1)
+  (func $reserve (param $end i32)
+    (if (i32.ge_u (local.get $end) (i32.shl (memory.size) (i32.const 16)))
+      (then
+        (if (i32.eq (memory.grow (i32.sub (i32.add (i32.shr_u (local.get $end) (i32.const 16)) (i32.const 1)) (memory.size))) (i32.const -1))
+          (then unreachable)
+        )
+      )
+    )
+  )
+  (func (export "main")
+    (local $p i32)
+    (block $break0
+      (loop $continue0
+        (br_if $break0 (i32.eqz (i32.load8_u (local.get $p))))
+        (i32.store8 (local.get $p) (i32.add (i32.load8_u (local.get $p)) (i32.const 255)))
+        (call $write (i32.load8_u (local.get $p)))
+        (br $continue0)
+      )
+    )
+  )
+)
+"#)
+	)
+}
+
+#[test]
+fn multiplication_loops_must_be_lowered_into_straight_line_code() {
+	assert_eq!(
+		to_wat(&compile(&parse_code("~냐? 냥냥!-").unwrap()).unwrap()),
+		s!(r#"(module
+  (import "env" "read" (func $read (result i32)))
+  (import "env" "write" (func $write (param i32)))
+  (memory (export "memory") 1)
+  (func $reserve (param $end i32)
+    (if (i32.ge_u (local.get $end) (i32.shl (memory.size) (i32.const 16)))
+      (then
+        (if (i32.eq (memory.grow (i32.sub (i32.add (i32.shr_u (local.get $end) (i32.const 16)) (i32.const 1)) (memory.size))) (i32.const -1))
+          (then unreachable)
+        )
+      )
+    )
+  )
+  (func (export "main")
+    (local $p i32)
+    (if (i32.ne (i32.load8_u (local.get $p)) (i32.const 0))
+      (then
+        (call $reserve (i32.add (local.get $p) (i32.const 1)))
+        (i32.store8 (i32.add (local.get $p) (i32.const 1)) (i32.add (i32.load8_u (i32.add (local.get $p) (i32.const 1))) (i32.mul (i32.load8_u (local.get $p)) (i32.const 2))))
+      )
+    )
+    (i32.store8 (local.get $p) (i32.const 0))
+  )
+)
+"#)
+	)
+}
+
+#[test]
+fn run_multiplication() {
+	assert_eq!(
+		run("냥냥냥~? 냥냥냥냥!냐-?.", b""),
+		[12]
+	)
+}
+
+#[test]
+fn run_skipped_multiplication_before_the_first_cell() {
+	assert_eq!(run("~냐! 냥?-.", b""), [0])
+}
+
+#[test]
+fn run_past_the_first_page() {
+	assert_eq!(run(&format!("{}냥.", "?".repeat(70_000)), b""), [1])
+}
+
+#[test]
+fn run_multiplication_into_the_next_page() {
+	assert_eq!(
+		run(&format!("{}냥~냐? 냥!-?.", "?".repeat(65_535)), b""),
+		[1]
+	)
+}
+
+#[test]
+fn run_hello_world() {
+	assert_eq!(
+		run(
+			include_str!("../../samples/hello.nyan"),
+			b""
+		),
+		b"Hello World!\n"
+	)
+}
+
+#[test]
+fn run_cat() {
+	assert_eq!(
+		run(
+			include_str!("../../samples/cat.nyan"),
+			"냥냥!".as_bytes()
+		),
+		"냥냥!".as_bytes()
+	)
+}
+
+#[test]
+fn run_reverse() {
+	assert_eq!(
+		run(
+			include_str!("../../samples/reverse.nyan"),
+			b"nyan"
+		),
+		b"nayn"
+	)
+}
@@ -22,11 +22,16 @@ macro_rules! word {
         $([$($body: expr),* $(,)?])?,
         $([$($tail: expr),* $(,)?])?
         $(,)?
+        $(; $($comment: expr),+ $(,)?)?
     ) => {
         Word {
             head: head_option!(Head, $($($head),*)?),
             body: head_option!(Body, $($($body),*)?),
             tail: head_option!(Tail, $($($tail),*)?),
+            comments: vec![$($(
+                $crate::parser::ast::Comment(String::from($comment))
+            ),+)?],
+            invalid: None,
         }
     };
 }
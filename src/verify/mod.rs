@@ -0,0 +1,154 @@
+use crate::{
+	format, format_code,
+	lexer::{Span, Token},
+	tokenize, CommentPlacement, Config, Error, FormatConfig, UnknownChars,
+};
+
+/// Tokens of the code other than line breaks, numbered in order, with the
+/// whitespace of comments changed as formatting changes it.
+fn compared(
+	tokens: impl IntoIterator<Item = Token>,
+	config: &FormatConfig,
+) -> Vec<(usize, Token)> {
+	tokens
+		.into_iter()
+		.filter(|tok| *tok != Token::NewLine)
+		.map(|tok| match tok {
+			Token::Comment(comment) => Token::Comment(
+				config
+					.comment_whitespace
+					.apply(&comment),
+			),
+			tok => tok,
+		})
+		.enumerate()
+		.collect()
+}
+
+/// Numbers of the first tokens that differ, with `None` standing for the
+/// end of the code.
+fn diverged(
+	input: &[(usize, Token)],
+	output: &[(usize, Token)],
+) -> Option<(Option<usize>, Option<usize>)> {
+	(0..input.len().max(output.len()))
+		.find(|&i| {
+			input.get(i).map(|(_, tok)| tok)
+				!= output.get(i).map(|(_, tok)| tok)
+		})
+		.map(|i| {
+			(
+				input.get(i).map(|&(n, _)| n),
+				output.get(i).map(|&(n, _)| n),
+			)
+		})
+}
+
+/// Span of the `n`th token of the code that is not a line break, for code
+/// that was lexed with the same policy for unknown characters.
+fn span(code: &str, unknown: UnknownChars, n: usize) -> Span {
+	tokenize(code, unknown)
+		.expect("the code was lexed already")
+		.into_iter()
+		.filter(|(tok, _)| *tok != Token::NewLine)
+		.nth(n)
+		.expect("the token was lexed already")
+		.1
+}
+
+/// Checks that formatting kept every instruction and comment in place.
+///
+/// Both the input and the output are lexed as the configuration tells, as
+/// unknown characters are kept verbatim in regions left unformatted, like
+/// `"nyanfmt: off"` regions and the code outside a range. Both are
+/// compared token by token, ignoring line breaks and whitespace, and
+/// comments are compared with their whitespace trimmed or normalized as
+/// the configuration tells. When comments are moved above their sentences,
+/// instructions and comments are compared apart, each in their own order.
+/// On mismatch, the spans of the first differing tokens are reported, with
+/// `None` standing for the end of the code.
+pub fn verify(
+	input: &str,
+	output: &str,
+	config: &FormatConfig,
+) -> Result<(), Error> {
+	let (input_tokens, spans): (Vec<_>, Vec<_>) =
+		tokenize(input, config.unknown_chars)?
+			.into_iter()
+			.filter(|(tok, _)| *tok != Token::NewLine)
+			.unzip();
+	let input_tokens = compared(input_tokens, config);
+	let output_tokens = compared(
+		tokenize(output, config.unknown_chars)?
+			.into_iter()
+			.map(|(tok, _)| tok),
+		config,
+	);
+
+	let diverged = match config.comment_placement {
+		CommentPlacement::Inline => diverged(&input_tokens, &output_tokens),
+		CommentPlacement::Above => {
+			let is_comment = |(_, tok): &(usize, Token)| {
+				matches!(tok, Token::Comment(_))
+			};
+			let (input_comments, input_tokens): (Vec<_>, Vec<_>) =
+				input_tokens
+					.into_iter()
+					.partition(is_comment);
+			let (output_comments, output_tokens): (Vec<_>, Vec<_>) =
+				output_tokens
+					.into_iter()
+					.partition(is_comment);
+
+			diverged(&input_tokens, &output_tokens)
+				.or_else(|| diverged(&input_comments, &output_comments))
+		},
+	};
+
+	match diverged {
+		Some((input_at, output_at)) => Err(Error::Diverged {
+			input: input_at.map(|n| spans[n]),
+			output: output_at
+				.map(|n| span(output, config.unknown_chars, n)),
+		}),
+		None => Ok(()),
+	}
+}
+
+/// Checks that formatting the already formatted code changes nothing.
+///
+/// On mismatch, the byte offset of the first difference in `formatted`
+/// is reported.
+pub fn check_idempotency(
+	formatted: &str,
+	config: &Config,
+) -> Result<(), Error> {
+	unchanged(formatted, &format_code(formatted, config)?)
+}
+
+/// Checks that the legacy token formatter leaves its own output unchanged,
+/// like [`check_idempotency`] does for the tree formatter.
+pub fn check_legacy_idempotency(formatted: &str) -> Result<(), Error> {
+	unchanged(formatted, &format(formatted.to_string()))
+}
+
+/// Fails at the byte offset of the first difference between the two.
+fn unchanged(formatted: &str, reformatted: &str) -> Result<(), Error> {
+	match formatted
+		.bytes()
+		.zip(reformatted.bytes())
+		.position(|(a, b)| a != b)
+	{
+		Some(at) => Err(Error::NotIdempotent { at }),
+		None if formatted.len() != reformatted.len() => {
+			Err(Error::NotIdempotent {
+				at: formatted.len().min(reformatted.len()),
+			})
+		},
+		None => Ok(()),
+	}
+}
+
+#[cfg(test)]
+#[path = "verify.spec.rs"]
+mod tests;
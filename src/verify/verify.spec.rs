@@ -0,0 +1,446 @@
+use super::*;
+use crate::{format_code, lexer::escape};
+use pretty_assertions::assert_eq;
+use proptest::{collection::vec, prelude::*, sample::select};
+use str_macro::str as s;
+
+#[test]
+fn whitespace_and_newlines_must_be_ignored() {
+	assert_eq!(
+		verify(
+			"\"주석\"냥냥 ?\n\n~ 냐-",
+			"\"주석\"\n냥냥?\n~냐-\n",
+			&FormatConfig::default(),
+		),
+		Ok(())
+	)
+}
+
+#[test]
+fn changed_token_must_be_reported() {
+	assert_eq!(
+		verify(
+			"냥냥?\n.",
+			"냥냐?\n.",
+			&FormatConfig::default()
+		),
+		Err(Error::Diverged {
+			input: Some(Span::new(3, 6)),
+			output: Some(Span::new(3, 6)),
+		})
+	)
+}
+
+#[test]
+fn changed_comment_must_be_reported() {
+	assert_eq!(
+		verify(
+			"\"a\" 냥",
+			"\"b\"\n냥",
+			&FormatConfig::default()
+		),
+		Err(Error::Diverged {
+			input: Some(Span::new(0, 3)),
+			output: Some(Span::new(0, 3)),
+		})
+	)
+}
+
+#[test]
+fn dropped_token_must_be_reported() {
+	assert_eq!(
+		verify(
+			"냥 . ,",
+			"냥.",
+			&FormatConfig::default()
+		),
+		Err(Error::Diverged {
+			input: Some(Span::new(6, 7)),
+			output: None,
+		})
+	)
+}
+
+#[test]
+fn added_token_must_be_reported() {
+	assert_eq!(
+		verify("냥", "냥!", &FormatConfig::default()),
+		Err(Error::Diverged {
+			input: None,
+			output: Some(Span::new(3, 4)),
+		})
+	)
+}
+
+#[test]
+fn unlexable_output_must_fail() {
+	assert_eq!(
+		verify("냥", "냥%", &FormatConfig::default()),
+		Err(Error::Lex {
+			at: Span::new(3, 4)
+		})
+	)
+}
+
+#[test]
+fn formatted_code_must_be_idempotent() {
+	assert_eq!(
+		check_idempotency(
+			"\"주석\"\n냥냥냥냥냥냥~?\n",
+			&Config::default()
+		),
+		Ok(())
+	)
+}
+
+#[test]
+fn unformatted_code_must_not_be_idempotent() {
+	assert_eq!(
+		check_idempotency(
+			"\"주석\"\n냥냥 냥~?\n",
+			&Config::default()
+		),
+		Err(Error::NotIdempotent { at: 15 })
+	)
+}
+
+#[test]
+fn missing_trailing_newline_must_not_be_idempotent() {
+	assert_eq!(
+		check_idempotency("냥", &Config::default()),
+		Err(Error::NotIdempotent { at: 3 })
+	)
+}
+
+#[test]
+fn legacy_formatter_output_must_be_idempotent() {
+	let formatted = crate::format(String::from("냥냐냐냥냐냥냐냥냥냥냐냥?냐"));
+
+	assert_eq!(
+		(
+			formatted.as_str(),
+			check_legacy_idempotency(&formatted),
+			check_legacy_idempotency("냥 냥")
+		),
+		(
+			"냥냐냐냥냐 냥냐냥냥냥 냐냥? 냐",
+			Ok(()),
+			Err(Error::NotIdempotent { at: 3 })
+		)
+	)
+}
+
+#[test]
+fn crlf_code_must_be_idempotent_with_crlf_config() {
+	let config = Config::from_toml("[format]\nnew-line = \"crlf\"").unwrap();
+	let formatted = format_code("\"주석\"\n냥냥 냥~?", &config).unwrap();
+
+	assert_eq!(
+		(
+			formatted.as_str(),
+			check_idempotency(&formatted, &config)
+		),
+		("\"주석\"\r\n냥냥냥~?\r\n", Ok(()))
+	)
+}
+
+#[test]
+fn multi_line_comments_must_be_kept_with_crlf_config() {
+	let config = Config::from_toml("[format]\nnew-line = \"crlf\"").unwrap();
+	let code = "\"a\nb\"\n냥\n";
+	let formatted = format_code(code, &config).unwrap();
+
+	assert_eq!(
+		(
+			formatted.as_str(),
+			verify(code, &formatted, &config.format)
+		),
+		("\"a\nb\"\r\n냥\r\n", Ok(()))
+	)
+}
+
+fn source(tokens: &[(Token, &str)]) -> String {
+	tokens
+		.iter()
+		.map(|(tok, space)| {
+			let tok = match tok {
+				Token::Right => s!("?"),
+				Token::Left => s!("!"),
+				Token::Inc => s!("냥"),
+				Token::Dec => s!("냐"),
+				Token::Out => s!("."),
+				Token::In => s!(","),
+				Token::JumpRight => s!("~"),
+				Token::JumpLeft => s!("-"),
+				Token::Debug => s!("뀨"),
+				Token::Comment(c) => format!("\"{}\"", escape(c)),
+				Token::NewLine => s!("\n"),
+				Token::Unknown(text) => text.clone(),
+			};
+
+			format!("{tok}{space}")
+		})
+		.collect()
+}
+
+fn token() -> impl Strategy<Value = Token> {
+	prop_oneof![
+		Just(Token::Right),
+		Just(Token::Left),
+		Just(Token::Inc),
+		Just(Token::Dec),
+		Just(Token::Out),
+		Just(Token::In),
+		Just(Token::JumpRight),
+		Just(Token::JumpLeft),
+		Just(Token::Debug),
+		"[a-z가-힣 \"\\\\\n]{0,8}".prop_map(Token::Comment),
+		Just(Token::NewLine),
+	]
+}
+
+fn code() -> impl Strategy<Value = String> {
+	vec(
+		(
+			token(),
+			prop_oneof![Just(""), Just(" "), Just("\t")],
+		),
+		0..64,
+	)
+	.prop_map(|tokens| source(&tokens))
+}
+
+/// Code with unknown characters, along with whether its line breaks are
+/// all `\r\n`, apart from those in comments, which are always `\n`.
+fn code_with_unknown_chars() -> impl Strategy<Value = (String, bool)> {
+	(
+		vec(
+			(
+				prop_oneof![
+					4 => token(),
+					1 => "[a-z%]{1,3}".prop_map(Token::Unknown),
+				],
+				prop_oneof![Just(""), Just(" "), Just("\t")],
+			),
+			0..64,
+		),
+		any::<bool>(),
+	)
+		.prop_map(|(tokens, crlf)| {
+			let code = tokens
+				.iter()
+				.map(|(tok, space)| match tok {
+					Token::NewLine if crlf => format!("\r\n{space}"),
+					tok => source(&[(tok.clone(), space)]),
+				})
+				.collect();
+			let crlf = crlf
+				&& tokens
+					.iter()
+					.any(|(tok, _)| *tok == Token::NewLine);
+
+			(code, crlf)
+		})
+}
+
+fn config() -> impl Strategy<Value = Config> {
+	(
+		select(vec!["lf", "crlf", "auto"]),
+		select(vec!["strip", "comment"]),
+		select(vec!["inline", "above"]),
+		select(vec!["trim", "normalize"]),
+	)
+		.prop_map(|(new_line, unknown, placement, whitespace)| {
+			Config::from_toml(&format!(
+				"[format]\nnew-line = \"{new_line}\"\n\
+				unknown-chars = \"{unknown}\"\n\
+				comment-placement = \"{placement}\"\n\
+				comment-whitespace = \"{whitespace}\""
+			))
+			.unwrap()
+		})
+}
+
+proptest! {
+	#[test]
+	fn formatting_must_preserve_tokens(code in code()) {
+		let formatted = format_code(&code, &Config::default())?;
+
+		prop_assert_eq!(verify(&code, &formatted, &FormatConfig::default()), Ok(()));
+	}
+
+	#[test]
+	fn formatting_must_be_idempotent(code in code()) {
+		let formatted = format_code(&code, &Config::default())?;
+
+		prop_assert_eq!(check_idempotency(&formatted, &Config::default()), Ok(()));
+	}
+
+	#[test]
+	fn moving_comments_above_must_preserve_tokens(code in code()) {
+		let config = Config::from_toml(
+			"[format]\ncomment-placement = \"above\"\ncomment-whitespace = \"normalize\"",
+		)
+		.unwrap();
+		let formatted = format_code(&code, &config)?;
+
+		prop_assert_eq!(verify(&code, &formatted, &config.format), Ok(()));
+		prop_assert_eq!(check_idempotency(&formatted, &config), Ok(()));
+	}
+
+	#[test]
+	fn formatting_must_preserve_tokens_with_any_config(
+		(code, _) in code_with_unknown_chars(),
+		config in config(),
+	) {
+		let formatted = format_code(&code, &config)?;
+
+		prop_assert_eq!(verify(&code, &formatted, &config.format), Ok(()));
+		prop_assert_eq!(check_idempotency(&formatted, &config), Ok(()));
+	}
+
+	#[test]
+	fn auto_new_line_must_follow_the_line_breaks_between_tokens(
+		(code, crlf) in code_with_unknown_chars(),
+	) {
+		let config = |new_line| {
+			Config::from_toml(&format!(
+				"[format]\nnew-line = \"{new_line}\"\n\
+				unknown-chars = \"comment\"\n\
+				comment-placement = \"above\""
+			))
+			.unwrap()
+		};
+
+		prop_assert_eq!(
+			format_code(&code, &config("auto"))?,
+			format_code(&code, &config(if crlf { "crlf" } else { "lf" }))?
+		);
+	}
+}
+
+#[test]
+fn unknown_chars_kept_as_comments_must_verify() {
+	let code = "냥냥 hi 냥?\n";
+	let config =
+		Config::from_toml("[format]\nunknown-chars = \"comment\"").unwrap();
+	let formatted = format_code(code, &config).unwrap();
+
+	assert_eq!(
+		(
+			formatted.as_str(),
+			verify(code, &formatted, &config.format),
+			check_idempotency(&formatted, &config),
+		),
+		("냥냥 \"hi\" 냥?\n", Ok(()), Ok(()))
+	)
+}
+
+#[test]
+fn stripped_unknown_chars_must_verify() {
+	let code = "냥냥 🐱 냥?\n";
+	let config =
+		Config::from_toml("[format]\nunknown-chars = \"strip\"").unwrap();
+	let formatted = format_code(code, &config).unwrap();
+
+	assert_eq!(
+		(
+			formatted.as_str(),
+			verify(code, &formatted, &config.format),
+		),
+		("냥냥냥?\n", Ok(()))
+	)
+}
+
+#[test]
+fn comments_moved_above_must_verify() {
+	let code = "냥 \"a\" 냥?\n냐 \"  b  c \"\n";
+	let config = Config::from_toml(
+		"[format]\ncomment-placement = \"above\"\ncomment-whitespace = \"normalize\"",
+	)
+	.unwrap();
+	let formatted = format_code(code, &config).unwrap();
+
+	assert_eq!(
+		(
+			formatted.as_str(),
+			verify(code, &formatted, &config.format),
+			check_idempotency(&formatted, &config),
+		),
+		(
+			"\"a\"\n냥냥?\n\n\"b c\"\n냐\n",
+			Ok(()),
+			Ok(())
+		)
+	)
+}
+
+#[test]
+fn moved_comment_must_be_reported_when_kept_inline() {
+	assert_eq!(
+		verify(
+			"냥 \"a\" 냥",
+			"\"a\"\n냥 냥",
+			&FormatConfig::default()
+		),
+		Err(Error::Diverged {
+			input: Some(Span::new(0, 3)),
+			output: Some(Span::new(0, 3)),
+		})
+	)
+}
+
+#[test]
+fn unknown_chars_left_outside_the_range_must_verify() {
+	let code = "냥 냥 hello\n냐  냐\n";
+
+	for policy in ["strip", "comment"] {
+		let config = Config::from_toml(&format!(
+			"[format]\nunknown-chars = \"{policy}\""
+		))
+		.unwrap();
+		let formatted = crate::apply_edits(
+			code,
+			&crate::format_code_range(
+				code,
+				Span::lines(code, 2, 2),
+				&config,
+			)
+			.unwrap(),
+		);
+
+		assert_eq!(
+			(
+				formatted.as_str(),
+				verify(code, &formatted, &config.format),
+			),
+			("냥 냥 hello\n냐냐\n", Ok(())),
+			"{policy}"
+		)
+	}
+}
+
+#[test]
+fn unknown_chars_in_off_regions_must_verify() {
+	let code = "\"nyanfmt: off\"\n냥 냥 hello\n\"nyanfmt: on\"\n냐  냐\n";
+
+	for policy in ["strip", "comment"] {
+		let config = Config::from_toml(&format!(
+			"[format]\nunknown-chars = \"{policy}\""
+		))
+		.unwrap();
+		let formatted = format_code(code, &config).unwrap();
+
+		assert_eq!(
+			(
+				formatted.as_str(),
+				verify(code, &formatted, &config.format),
+			),
+			(
+				"\"nyanfmt: off\"\n냥 냥 hello\n\"nyanfmt: on\"\n냐냐\n",
+				Ok(())
+			),
+			"{policy}"
+		)
+	}
+}
@@ -0,0 +1,114 @@
+//! Bindings for running the formatter and the interpreter in the browser,
+//! built with `wasm-pack build --features wasm`.
+//!
+//! Failures are thrown as [`JsError`] objects.
+
+use serde::Serialize;
+use serde_wasm_bindgen::Serializer;
+use wasm_bindgen::prelude::*;
+
+use crate::{Config, Error, Span};
+
+/// Position of a span, with offsets counted in UTF-16 code units so that
+/// `code.slice(start, end)` gives the spanned text in JS.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct JsSpan {
+	pub start: usize,
+	pub end: usize,
+	/// one-based
+	pub line: usize,
+	/// one-based, in characters
+	pub column: usize,
+}
+
+impl JsSpan {
+	fn new(code: &str, span: Span) -> Self {
+		let utf16 = |offset: usize| code[..offset].encode_utf16().count();
+		let (line, column) = span.line_col(code);
+
+		Self {
+			start: utf16(span.start),
+			end: utf16(span.end),
+			line,
+			column,
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct JsError {
+	pub kind: &'static str,
+	pub message: String,
+	/// where the code failed, or `null` if the error is not tied to a
+	/// token
+	pub span: Option<JsSpan>,
+}
+
+impl JsError {
+	pub fn new(code: &str, err: &Error) -> Self {
+		let (kind, span) = match err {
+			Error::Lex { at } => ("lex", Some(*at)),
+			Error::UnterminatedComment { at } => {
+				("unterminatedComment", Some(*at))
+			},
+			Error::Parse { at } => ("parse", Some(*at)),
+			Error::UnmatchedJumpRight => ("unmatchedJumpRight", None),
+			Error::UnmatchedJumpLeft => ("unmatchedJumpLeft", None),
+			Error::PointerUnderflow => ("pointerUnderflow", None),
+			Error::Io(_) => ("io", None),
+			Error::Diverged { input, .. } => ("diverged", *input),
+			Error::NotIdempotent { at } => (
+				"notIdempotent",
+				Some(Span::new(*at, *at)),
+			),
+			Error::Config(_) => ("config", None),
+			Error::Protocol(_) => ("protocol", None),
+			Error::InvalidAst(_) => ("invalidAst", None),
+		};
+
+		Self {
+			kind,
+			message: err.to_string(),
+			span: span.map(|span| JsSpan::new(code, span)),
+		}
+	}
+}
+
+fn throw(code: &str, err: Error) -> JsValue {
+	JsError::new(code, &err)
+		.serialize(&Serializer::json_compatible())
+		.unwrap_or_else(JsValue::from)
+}
+
+/// Runs the code, decoding its output as UTF-8 and replacing invalid
+/// sequences.
+pub fn run(code: &str, input: &str) -> Result<String, Error> {
+	let mut output = vec![];
+	crate::run_code(code, input.as_bytes(), &mut output)?;
+
+	Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+#[wasm_bindgen(js_name = parseAndFormatCode)]
+pub fn parse_and_format_code(code: &str) -> Result<String, JsValue> {
+	crate::format_code(code, &Config::default()).map_err(|err| throw(code, err))
+}
+
+/// Parses the code into the tree [`crate::AstFormat::Json`] prints, as a JS
+/// object.
+#[wasm_bindgen(js_name = dumpAst)]
+pub fn dump_ast(code: &str) -> Result<JsValue, JsValue> {
+	crate::parse_code(code)
+		.map_err(|err| throw(code, err))?
+		.serialize(&Serializer::json_compatible())
+		.map_err(JsValue::from)
+}
+
+#[wasm_bindgen(js_name = runCode)]
+pub fn run_code(code: &str, input: &str) -> Result<String, JsValue> {
+	run(code, input).map_err(|err| throw(code, err))
+}
+
+#[cfg(test)]
+#[path = "wasm.spec.rs"]
+mod tests;
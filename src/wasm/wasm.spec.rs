@@ -0,0 +1,64 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn lex_error_must_point_at_character_in_utf16() {
+	let code = "냥냥\n냥a";
+
+	assert_eq!(
+		crate::format_code(code, &Config::default())
+			.map_err(|err| JsError::new(code, &err)),
+		Err(JsError {
+			kind: "lex",
+			message: String::from("failed to tokenize the code"),
+			span: Some(JsSpan {
+				start: 4,
+				end: 5,
+				line: 2,
+				column: 2,
+			}),
+		})
+	)
+}
+
+#[test]
+fn unterminated_comment_must_point_at_its_opening_quote() {
+	let code = "냥\n\"주석\n냥";
+
+	assert_eq!(
+		crate::format_code(code, &Config::default())
+			.map_err(|err| JsError::new(code, &err)),
+		Err(JsError {
+			kind: "unterminatedComment",
+			message: String::from("comment is never closed"),
+			span: Some(JsSpan {
+				start: 2,
+				end: 3,
+				line: 2,
+				column: 1,
+			}),
+		})
+	)
+}
+
+#[test]
+fn runtime_error_must_have_no_span() {
+	let code = "냥!";
+
+	assert_eq!(
+		run(code, "").map_err(|err| JsError::new(code, &err)),
+		Err(JsError {
+			kind: "pointerUnderflow",
+			message: Error::PointerUnderflow.to_string(),
+			span: None,
+		})
+	)
+}
+
+#[test]
+fn run_must_decode_output() {
+	assert_eq!(
+		run(",.,.,.", "냥"),
+		Ok(String::from("냥"))
+	)
+}
@@ -0,0 +1,50 @@
+use std::{env, fs, path::PathBuf, process::Command};
+
+/// Writes the code into a fresh directory, returning the path of the code.
+fn file(dir: &str, code: &str) -> PathBuf {
+	let dir = env::temp_dir().join(dir);
+	let _ = fs::remove_dir_all(&dir);
+	fs::create_dir_all(&dir).unwrap();
+	fs::write(dir.join("a.nyan"), code).unwrap();
+
+	dir.join("a.nyan")
+}
+
+/// Runs the CLI, returning its exit code and what it wrote to stderr.
+fn run(args: &[&str]) -> (Option<i32>, String) {
+	let output = Command::new(env!("CARGO_BIN_EXE_nyanfmt"))
+		.args(args)
+		.output()
+		.unwrap();
+
+	(
+		output.status.code(),
+		String::from_utf8(output.stderr).unwrap(),
+	)
+}
+
+#[test]
+fn missing_file_must_fail_without_panicking() {
+	let (code, stderr) = run(&["nyanfmt-missing-file.nyan"]);
+
+	assert_eq!(code, Some(1));
+	assert!(stderr.starts_with("Can't read nyanfmt-missing-file.nyan: "));
+	assert!(!stderr.contains("panicked"));
+}
+
+#[test]
+fn code_errors_must_be_reported_at_their_position() {
+	let file = file("nyanfmt-cli-lint-error", "냥\n냥a");
+	let file = file.to_str().unwrap();
+
+	assert_eq!(
+		run(&["lint", file]),
+		(
+			Some(1),
+			format!(
+				"Can't lint {file}: failed to tokenize the code at {}\n",
+				"line 2, column 2 (`a`)"
+			)
+		)
+	)
+}